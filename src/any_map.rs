@@ -0,0 +1,129 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    hash::Hash,
+};
+
+use crate::Slock;
+
+type Entries<K> = HashMap<(K, TypeId), Box<dyn Any + Send + Sync>>;
+
+/// A registry of locks with different value types, keyed by key and value type.
+///
+/// The same key can hold one lock per value type. Lookups name the value type, and only find locks of that type.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// struct Volume(f32);
+///
+/// let plugins = AnySlockMap::new();
+/// plugins.insert("audio", Volume(0.8)).await;
+/// plugins.insert("audio", vec!["click.wav"]).await;
+///
+/// let volume = plugins.get::<Volume>("audio").await.unwrap();
+/// volume.set(|_| Volume(0.5)).await;
+/// assert!(plugins.get::<String>("audio").await.is_none());
+/// # };
+/// ```
+pub struct AnySlockMap<K> {
+    entries: Slock<Entries<K>>,
+}
+
+impl<K> AnySlockMap<K> {
+    /// Create a new, empty registry.
+    pub fn new() -> Self {
+        Self {
+            entries: Slock::new(HashMap::new()),
+        }
+    }
+
+    /// The number of locks, across every value type.
+    pub async fn len(&self) -> usize {
+        self.entries.peek(|entries| entries.len()).await
+    }
+
+    /// Returns true if there are no locks.
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
+
+impl<K: Eq + Hash + Copy> AnySlockMap<K> {
+    /// Insert a value at `key` in its own lock, or set the existing lock of the same type there.
+    pub async fn insert<V: Send + 'static>(&self, key: K, value: V) {
+        // Waiting for room in the write queue never fails
+        let _queued = self.entries.enqueue(false).await;
+        let mut data = self.entries.write_as(None).await;
+        match data.value.get(&(key, TypeId::of::<V>())) {
+            Some(inner) => {
+                let inner = downcast::<V>(&**inner);
+                // Setting the lock waits on it, so it happens after the registry is unlocked
+                drop(data);
+                inner.set(|_| value).await;
+            }
+            None => {
+                let inner = Box::new(Slock::new(value));
+                data.value.insert((key, TypeId::of::<V>()), inner);
+                self.entries.publish(&mut data).await;
+            }
+        }
+    }
+
+    /// Get the lock holding a `V` at `key`, if there is one.
+    pub async fn get<V: 'static>(&self, key: K) -> Option<Slock<V>> {
+        self.entries
+            .peek(|entries| {
+                let entry = entries.get(&(key, TypeId::of::<V>()))?;
+                Some(downcast(&**entry))
+            })
+            .await
+    }
+
+    /// Remove the lock holding a `V` at `key`, returning it. Locks of other types at `key` are kept.
+    pub async fn remove<V: 'static>(&self, key: K) -> Option<Slock<V>> {
+        let mut removed = None;
+        self.entries
+            .set(|mut entries| {
+                removed = entries.remove(&(key, TypeId::of::<V>()));
+                entries
+            })
+            .await;
+        Some(downcast(&*removed?))
+    }
+
+    /// The keys holding a `V`.
+    pub async fn keys_of<V: 'static>(&self) -> Vec<K> {
+        let type_id = TypeId::of::<V>();
+        self.entries
+            .peek(|entries| {
+                entries
+                    .keys()
+                    .filter(|(_, id)| *id == type_id)
+                    .map(|(key, _)| *key)
+                    .collect()
+            })
+            .await
+    }
+}
+
+/// Entries are only ever stored under their own type's id.
+fn downcast<V: 'static>(entry: &(dyn Any + Send + Sync)) -> Slock<V> {
+    entry
+        .downcast_ref::<Slock<V>>()
+        .expect("entries are keyed by their value type")
+        .clone()
+}
+
+impl<K> Default for AnySlockMap<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Clone for AnySlockMap<K> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+        }
+    }
+}
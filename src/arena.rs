@@ -0,0 +1,190 @@
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::{RwLock, RwLockWriteGuard};
+
+use crate::Error;
+
+/// A handle to one value in a [`SlockArena`].
+///
+/// Keys are never reused: once a value is removed, its key stays invalid even if the slot is filled again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArenaKey {
+    index: u32,
+    generation: u32,
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+struct ArenaInner<T> {
+    /// Every slot lives in one allocation, made when the arena is created.
+    slots: Box<[RwLock<Slot<T>>]>,
+    free: Mutex<Vec<u32>>,
+}
+
+/// Many small, independently locked values sharing a single allocation.
+///
+/// Each value is locked on its own, like a [`Slock`](crate::Slock), but without a reference count,
+/// version or hook of its own. Suited to tens of thousands of tiny values, such as game entities.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let positions = SlockArena::with_capacity(1024);
+/// let player = positions.insert((0.0f32, 0.0f32)).await.unwrap();
+///
+/// positions.set(player, |(x, y)| (x + 1.0, y)).await.unwrap();
+/// assert_eq!(positions.map(player, |p| p.0).await.unwrap(), Some(1.0));
+/// # };
+/// ```
+pub struct SlockArena<T> {
+    inner: Arc<ArenaInner<T>>,
+}
+
+impl<T> SlockArena<T> {
+    /// Create an arena with room for `capacity` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` doesn't fit in a `u32`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = u32::try_from(capacity).expect("arena capacity should fit in a u32");
+        let slots = (0..capacity)
+            .map(|_| {
+                RwLock::new(Slot {
+                    generation: 0,
+                    value: None,
+                })
+            })
+            .collect();
+        Self {
+            inner: Arc::new(ArenaInner {
+                slots,
+                free: Mutex::new((0..capacity).rev().collect()),
+            }),
+        }
+    }
+
+    /// The number of values the arena can hold.
+    pub fn capacity(&self) -> usize {
+        self.inner.slots.len()
+    }
+
+    /// The number of values currently in the arena.
+    pub fn len(&self) -> usize {
+        self.capacity() - self.inner.free.lock().unwrap().len()
+    }
+
+    /// Returns `true` if the arena holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Store a new value, returning its key.
+    ///
+    /// If the arena is full, the value is handed back.
+    pub async fn insert(&self, value: T) -> Result<ArenaKey, T> {
+        let Some(index) = self.inner.free.lock().unwrap().pop() else {
+            return Err(value);
+        };
+        let mut slot = self.inner.slots[index as usize].write().await;
+        slot.value = Some(value);
+        Ok(ArenaKey {
+            index,
+            generation: slot.generation,
+        })
+    }
+
+    /// Remove the value at `key`, returning it.
+    ///
+    /// `None` if the key has already been removed.
+    pub async fn remove(&self, key: ArenaKey) -> Option<T> {
+        let mut slot = self.inner.slots[key.index as usize].write().await;
+        if slot.generation != key.generation {
+            return None;
+        }
+        let value = slot.value.take();
+        self.free(slot, key.index);
+        value
+    }
+
+    /// Empties a slot for reuse, invalidating its key.
+    fn free(&self, mut slot: RwLockWriteGuard<'_, Slot<T>>, index: u32) {
+        slot.value = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        drop(slot);
+        self.inner.free.lock().unwrap().push(index);
+    }
+
+    /// Returns `true` if `key` still refers to a value.
+    pub async fn contains(&self, key: ArenaKey) -> bool {
+        let slot = self.inner.slots[key.index as usize].read().await;
+        slot.generation == key.generation && slot.value.is_some()
+    }
+
+    /// Extract inner values from the value at `key`, like [`Slock::map`](crate::Slock::map).
+    ///
+    /// `None` if the key has been removed, or [`Error::ClosurePanicked`] if `mapper` panics.
+    pub async fn map<F, U>(&self, key: ArenaKey, mapper: F) -> Result<Option<U>, Error>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        let slot = self.inner.slots[key.index as usize].read().await;
+        if slot.generation != key.generation {
+            return Ok(None);
+        }
+        let Some(value) = slot.value.as_ref() else {
+            return Ok(None);
+        };
+        catch_unwind(AssertUnwindSafe(|| mapper(value)))
+            .map(Some)
+            .map_err(|_| Error::ClosurePanicked)
+    }
+
+    /// Change the value at `key`, like [`Slock::set`](crate::Slock::set).
+    ///
+    /// Returns `false` if the key has been removed.
+    /// If `setter` panics, it takes the value with it, so the key is removed and [`Error::ClosurePanicked`] is returned.
+    pub async fn set<F>(&self, key: ArenaKey, setter: F) -> Result<bool, Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        let mut slot = self.inner.slots[key.index as usize].write().await;
+        if slot.generation != key.generation {
+            return Ok(false);
+        }
+        let Some(value) = slot.value.take() else {
+            return Ok(false);
+        };
+        match catch_unwind(AssertUnwindSafe(|| setter(value))) {
+            Ok(new) => {
+                slot.value = Some(new);
+                Ok(true)
+            }
+            Err(_) => {
+                // There's no value left to put back, so the slot is freed rather than leaked
+                self.free(slot, key.index);
+                Err(Error::ClosurePanicked)
+            }
+        }
+    }
+}
+
+impl<T: Clone> SlockArena<T> {
+    /// Clone the value at `key`, or `None` if the key has been removed.
+    pub async fn get_clone(&self, key: ArenaKey) -> Result<Option<T>, Error> {
+        self.map(key, T::clone).await
+    }
+}
+
+impl<T> Clone for SlockArena<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
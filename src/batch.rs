@@ -0,0 +1,51 @@
+use crate::Slock;
+
+type Setter<'a, T> = Box<dyn FnOnce(T) -> T + Send + 'a>;
+
+/// Setters queued by [`Slock::batch`].
+pub struct Batch<'a, T> {
+    setters: Vec<Setter<'a, T>>,
+}
+
+impl<'a, T> Batch<'a, T> {
+    /// Queue a setter, to run after every setter queued before it.
+    pub fn set<F>(&mut self, setter: F) -> &mut Self
+    where
+        F: FnOnce(T) -> T + Send + 'a,
+    {
+        self.setters.push(Box::new(setter));
+        self
+    }
+}
+
+impl<T> Slock<T> {
+    /// Apply several setters under a single write acquisition.
+    ///
+    /// The hook runs once, with the final value, and observers never see the intermediate values.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let player = Slock::new((100i32, 0u32));
+    /// player.batch(|b| {
+    ///     b.set(|(hp, xp)| (hp - 10, xp))
+    ///      .set(|(hp, xp)| (hp, xp + 50));
+    /// }).await;
+    /// # };
+    /// ```
+    pub async fn batch<'a, F>(&self, build: F)
+    where
+        F: FnOnce(&mut Batch<'a, T>),
+    {
+        let mut batch = Batch {
+            setters: Vec::new(),
+        };
+        build(&mut batch);
+        self.set(|value| {
+            batch
+                .setters
+                .into_iter()
+                .fold(value, |value, setter| setter(value))
+        })
+        .await;
+    }
+}
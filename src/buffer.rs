@@ -0,0 +1,48 @@
+use std::ops::RangeBounds;
+
+use bytes::{Bytes, BytesMut};
+
+use crate::Slock;
+
+/// ## Byte buffers
+///
+/// `Bytes` are reference counted, so reads hand out views of the buffer instead of copies.
+impl Slock<Bytes> {
+    /// Returns the whole buffer without copying it.
+    /// ```rust
+    /// # use slock::*;
+    /// # use bytes::Bytes;
+    /// # async {
+    /// let frame = Slock::new(Bytes::from_static(b"hello world"));
+    /// assert_eq!(frame.get_bytes().await, "hello world");
+    /// # };
+    /// ```
+    pub async fn get_bytes(&self) -> Bytes {
+        self.read().await.value.clone()
+    }
+
+    /// Returns part of the buffer without copying it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, like [`Bytes::slice`].
+    pub async fn slice(&self, range: impl RangeBounds<usize>) -> Bytes {
+        self.read().await.value.slice(range)
+    }
+
+    /// Add `tail` to the end of the buffer.
+    ///
+    /// An empty buffer is replaced with `tail` outright, without copying either.
+    pub async fn append(&self, tail: Bytes) {
+        self.set(|head| {
+            if head.is_empty() {
+                return tail;
+            }
+            let mut joined = BytesMut::with_capacity(head.len() + tail.len());
+            joined.extend_from_slice(&head);
+            joined.extend_from_slice(&tail);
+            joined.freeze()
+        })
+        .await;
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    error::Error,
+    mem::ManuallyDrop,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::{watch, RwLock};
+
+#[cfg(feature = "chaos")]
+use crate::chaos::Injector;
+#[cfg(feature = "rt")]
+use crate::notify::Notifier;
+#[cfg(feature = "record")]
+use crate::record::History;
+use crate::{
+    metrics::Counters, queue::WriteQueue, stream::Feed, validate::Validator, Slock, SlockData,
+    SlockInner, WhenFull, NEXT_SERIAL,
+};
+
+/// Configures a [`Slock`] before it is created.
+///
+/// ```rust
+/// # use slock::*;
+/// # use std::time::Duration;
+/// let config = Slock::builder()
+///     .named("config")
+///     .timeout(Duration::from_millis(250))
+///     .validator(|v: &String| if v.is_empty() { Err("empty config") } else { Ok(()) })
+///     .build(String::from("debug = false"));
+///
+/// assert_eq!(config.name(), Some("config"));
+/// ```
+pub struct SlockBuilder<T> {
+    timeout: Duration,
+    name: Option<String>,
+    metrics: bool,
+    validator: Option<Validator<T>>,
+    write_queue: Option<WriteQueue>,
+    feed: Option<Feed<T>>,
+    #[cfg(feature = "rt")]
+    pub(crate) notifier: Option<Notifier<T>>,
+    #[cfg(feature = "record")]
+    history: Option<History<T>>,
+    #[cfg(feature = "chaos")]
+    pub(crate) chaos: Option<Injector>,
+}
+
+impl<T> Slock<T> {
+    /// Start configuring a new Slock.
+    ///
+    /// Starts from the defaults set with [`configure`](crate::configure).
+    pub fn builder() -> SlockBuilder<T> {
+        let config = crate::config();
+        SlockBuilder {
+            timeout: config.default_timeout,
+            name: None,
+            metrics: config.metrics_enabled,
+            validator: None,
+            write_queue: None,
+            feed: None,
+            #[cfg(feature = "rt")]
+            notifier: None,
+            #[cfg(feature = "record")]
+            history: None,
+            #[cfg(feature = "chaos")]
+            chaos: None,
+        }
+    }
+}
+
+impl<T: Clone> SlockBuilder<T> {
+    /// Reject any value that fails `validator`. See [`Slock::new_validated`].
+    pub fn validator<F, E>(mut self, validator: F) -> Self
+    where
+        F: Fn(&T) -> Result<(), E> + Send + Sync + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.validator = Some(Validator::new(validator));
+        self
+    }
+
+    /// Let each [`Slock::changes`] stream buffer up to `capacity` unread values. Defaults to 16.
+    pub fn change_buffer(mut self, capacity: usize) -> Self {
+        self.feed = Some(Feed::new(capacity));
+        self
+    }
+
+    /// Record the most recent `capacity` values from the start. See [`Slock::record`].
+    #[cfg(feature = "record")]
+    pub fn history(mut self, capacity: usize) -> Self {
+        self.history = Some(History::new(capacity));
+        self
+    }
+}
+
+impl<T> SlockBuilder<T> {
+    /// How long closures may run while the lock is held. Defaults to one second, or [`GlobalConfig::default_timeout`](crate::GlobalConfig::default_timeout).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Give the lock a name, for diagnostics.
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Whether to count acquisitions, wait time and timeouts. Defaults to `true`, or [`GlobalConfig::metrics_enabled`](crate::GlobalConfig::metrics_enabled).
+    pub fn metrics(mut self, enabled: bool) -> Self {
+        self.metrics = enabled;
+        self
+    }
+
+    /// Allow at most `capacity` writes to wait on, or hold, the lock at once.
+    ///
+    /// `when_full` decides what [`Slock::set`] and [`Slock::try_set`] do once the queue is full.
+    /// Other writes always wait for room.
+    pub fn write_queue(mut self, capacity: usize, when_full: WhenFull) -> Self {
+        self.write_queue = Some(WriteQueue::new(capacity, when_full));
+        self
+    }
+
+    /// Create the lock with a given initial value.
+    pub fn build(self, value: T) -> Slock<T> {
+        #[cfg(feature = "record")]
+        let mut history = self.history;
+        #[cfg(feature = "record")]
+        if let Some(history) = history.as_mut() {
+            history.push(0, &value);
+        }
+        let data = SlockData {
+            version: 0,
+            value,
+            hook: None,
+            validator: self.validator,
+            feed: self.feed,
+            #[cfg(feature = "rt")]
+            notifier: self.notifier,
+            #[cfg(feature = "record")]
+            history,
+        };
+        Slock {
+            lock: Arc::new(SlockInner {
+                data: ManuallyDrop::new(RwLock::new(data)),
+                serial: NEXT_SERIAL.fetch_add(1, Ordering::Relaxed),
+                poisoned: AtomicBool::new(false),
+                changes: watch::channel(0).0,
+                set_notify: Default::default(),
+                counters: Arc::new(Counters::new(self.metrics)),
+                timeout: self.timeout,
+                name: self.name,
+                recompute: Default::default(),
+                write_queue: self.write_queue,
+                pause: Default::default(),
+                map_listeners: Default::default(),
+                temp_hooks: Default::default(),
+                #[cfg(feature = "time")]
+                coalesce: Default::default(),
+                #[cfg(feature = "time")]
+                leases: Default::default(),
+                #[cfg(feature = "time")]
+                accessed: std::sync::Mutex::new(crate::time::Instant::now()),
+                #[cfg(feature = "chaos")]
+                chaos: self.chaos,
+            }),
+        }
+    }
+}
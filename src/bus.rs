@@ -0,0 +1,90 @@
+use std::{
+    any::{Any, TypeId},
+    future::Future,
+    sync::Arc,
+};
+
+use futures::future::{self, BoxFuture};
+
+use crate::SlockMap;
+
+type Event = Arc<dyn Any + Send + Sync>;
+type Handler = Arc<dyn Fn(Event) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// A typed publish / subscribe bus.
+///
+/// Subscribers register for a single event type, and are called with every event of that type.
+/// ```rust
+/// # use slock::*;
+/// struct UserJoined(u64);
+///
+/// # async {
+/// let bus = SlockBus::new();
+/// bus.subscribe(|event: std::sync::Arc<UserJoined>| async move {
+///     println!("welcome, user {}", event.0);
+/// }).await;
+///
+/// bus.publish(UserJoined(7)).await;
+/// # };
+/// ```
+pub struct SlockBus {
+    subscribers: SlockMap<TypeId, Vec<Handler>>,
+}
+
+impl SlockBus {
+    /// Create a bus with no subscribers.
+    pub fn new() -> Self {
+        Self {
+            subscribers: SlockMap::new_map(),
+        }
+    }
+
+    /// Call `handler` with every event of type `E` published from now on.
+    pub async fn subscribe<E, F, Fut>(&self, handler: F)
+    where
+        E: Any + Send + Sync,
+        F: Fn(Arc<E>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handler: Handler = Arc::new(move |event: Event| match event.downcast::<E>() {
+            Ok(event) => Box::pin(handler(event)),
+            Err(_) => unreachable!("handlers are keyed by their event type"),
+        });
+        // The list is created under a single write of the map, so concurrent first subscribers share it
+        self.subscribers
+            .at(TypeId::of::<E>())
+            .await
+            .push(handler)
+            .await;
+    }
+
+    /// Publish an event, waiting for every subscriber to handle it.
+    ///
+    /// Subscribers run concurrently. Returns the number of subscribers called.
+    pub async fn publish<E>(&self, event: E) -> usize
+    where
+        E: Any + Send + Sync,
+    {
+        let Some(handlers) = self.subscribers.from_key(TypeId::of::<E>()).await else {
+            return 0;
+        };
+        let handlers = handlers.get_clone().await;
+        let event: Event = Arc::new(event);
+        future::join_all(handlers.iter().map(|handler| handler(event.clone()))).await;
+        handlers.len()
+    }
+}
+
+impl Default for SlockBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for SlockBus {
+    fn clone(&self) -> Self {
+        Self {
+            subscribers: self.subscribers.clone(),
+        }
+    }
+}
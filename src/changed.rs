@@ -0,0 +1,60 @@
+use std::{
+    future::{Future, IntoFuture},
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tokio::sync::futures::Notified;
+
+use crate::Slock;
+
+/// Resolves the next time a lock is set, returned by [`Slock::changed`].
+///
+/// Awaiting a `&Slock` directly does the same, so a lock can be used as a branch of `select!` as is.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let lock = Slock::new(0i32);
+/// let shutdown = Slock::new(false);
+///
+/// loop {
+///     tokio::select! {
+///         _ = &lock => println!("now {}", lock.get().await),
+///         _ = &shutdown => break,
+///     }
+/// }
+/// # };
+/// ```
+pub struct Changed<'a, T> {
+    notified: Notified<'a>,
+    lock: PhantomData<&'a Slock<T>>,
+}
+
+impl<'a, T> Changed<'a, T> {
+    pub(crate) fn new(lock: &'a Slock<T>) -> Self {
+        Self {
+            notified: lock.lock.set_notify.notified(),
+            lock: PhantomData,
+        }
+    }
+}
+
+impl<T> Future for Changed<'_, T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // `notified` is never moved out of the pinned `Changed`
+        let notified = unsafe { self.map_unchecked_mut(|changed| &mut changed.notified) };
+        notified.poll(cx)
+    }
+}
+
+impl<'a, T> IntoFuture for &'a Slock<T> {
+    type Output = ();
+    type IntoFuture = Changed<'a, T>;
+
+    fn into_future(self) -> Changed<'a, T> {
+        self.changed()
+    }
+}
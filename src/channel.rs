@@ -0,0 +1,75 @@
+use std::pin::pin;
+
+use futures::future::{self, Either};
+use tokio::sync::watch;
+
+use crate::Slock;
+
+impl<T: Clone + Send + Sync + 'static> Slock<T> {
+    /// Create a lock that mirrors a `watch` channel, starting from its current value.
+    ///
+    /// A spawned task sets the lock whenever the channel changes. It stops once the channel's sender,
+    /// or every handle to the lock, has been dropped.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let (sender, receiver) = tokio::sync::watch::channel(1i32);
+    /// let lock = Slock::from_watch(receiver);
+    ///
+    /// sender.send(2).unwrap();
+    /// # };
+    /// ```
+    pub fn from_watch(mut receiver: watch::Receiver<T>) -> Slock<T> {
+        let lock = Slock::new(receiver.borrow_and_update().clone());
+        let weak = lock.downgrade();
+        tokio::spawn(async move {
+            while receiver.changed().await.is_ok() {
+                let Some(lock) = Slock::upgrade(&weak) else {
+                    break;
+                };
+                let value = receiver.borrow_and_update().clone();
+                lock.set(|_| value).await;
+            }
+        });
+        lock
+    }
+
+    /// Create a `watch` channel that follows the lock's value.
+    ///
+    /// A spawned task sends the latest value after every set. It stops once every receiver,
+    /// or every handle to the lock, has been dropped.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let lock = Slock::new(1i32);
+    /// let mut receiver = lock.to_watch().await;
+    ///
+    /// lock.set(|v| v + 1).await;
+    /// receiver.changed().await.unwrap();
+    /// assert_eq!(*receiver.borrow(), 2);
+    /// # };
+    /// ```
+    pub async fn to_watch(&self) -> watch::Receiver<T> {
+        // Subscribed first, so no set after the initial read is missed
+        let mut changes = self.lock.changes.subscribe();
+        changes.borrow_and_update();
+        let (sender, receiver) = watch::channel(self.get_clone().await);
+        let weak = self.downgrade();
+        tokio::spawn(async move {
+            loop {
+                let changed = pin!(changes.changed());
+                let closed = pin!(sender.closed());
+                match future::select(changed, closed).await {
+                    Either::Left((Ok(()), _)) => {}
+                    _ => break,
+                }
+                let Some(lock) = Slock::upgrade(&weak) else {
+                    break;
+                };
+                let value = lock.get_clone().await;
+                sender.send_replace(value);
+            }
+        });
+        receiver
+    }
+}
@@ -0,0 +1,116 @@
+use std::{sync::Mutex, time::Duration};
+
+use crate::SlockBuilder;
+
+/// Faults to inject into a lock, for checking that an application tolerates worst-case lock behavior.
+///
+/// Every decision is drawn from a generator seeded by [`Chaos::seeded`], so a failing run can be reproduced.
+/// ```rust
+/// # use slock::*;
+/// # use std::time::Duration;
+/// let lock = Slock::builder()
+///     .chaos(
+///         Chaos::seeded(42)
+///             .delays(Duration::from_millis(5))
+///             .timeouts(0.1)
+///             .reorder_writes(),
+///     )
+///     .build(0i32);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Chaos {
+    seed: u64,
+    delay: Duration,
+    timeout_rate: f64,
+    reorder: bool,
+}
+
+impl Chaos {
+    /// Inject nothing yet, drawing every later decision from `seed`.
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            seed,
+            delay: Duration::ZERO,
+            timeout_rate: 0.0,
+            reorder: false,
+        }
+    }
+
+    /// Wait a random time up to `max` before every acquisition.
+    pub fn delays(mut self, max: Duration) -> Self {
+        self.delay = max;
+        self
+    }
+
+    /// Fail this fraction of contained operations with [`Error::Timeout`](crate::Error::Timeout),
+    /// without running them.
+    ///
+    /// Only user closures are timed out. Helpers like [`Slock::toggle`](crate::Slock::toggle) run their own closures
+    /// uncontained, so they keep working, and helpers taking a closure return the timeout like [`Slock::map`](crate::Slock::map).
+    pub fn timeouts(mut self, rate: f64) -> Self {
+        self.timeout_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Yield to the runtime a random number of times before queuing for the write lock,
+    /// so concurrent writers acquire it out of order.
+    pub fn reorder_writes(mut self) -> Self {
+        self.reorder = true;
+        self
+    }
+}
+
+impl<T> SlockBuilder<T> {
+    /// Inject faults into every operation on the lock. See [`Chaos`].
+    pub fn chaos(mut self, chaos: Chaos) -> Self {
+        self.chaos = Some(Injector::new(chaos));
+        self
+    }
+}
+
+/// A lock's fault configuration, along with its random state.
+pub(crate) struct Injector {
+    chaos: Chaos,
+    state: Mutex<u64>,
+}
+
+impl Injector {
+    fn new(chaos: Chaos) -> Self {
+        Self {
+            state: Mutex::new(chaos.seed),
+            chaos,
+        }
+    }
+
+    /// SplitMix64, which is plenty for picking faults.
+    fn next(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniform value in `[0, 1)`.
+    fn unit(&self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Runs before every acquisition.
+    pub(crate) async fn acquire(&self, write: bool) {
+        if !self.chaos.delay.is_zero() {
+            crate::time::sleep(self.chaos.delay.mul_f64(self.unit())).await;
+        }
+        if write && self.chaos.reorder {
+            for _ in 0..self.next() % 8 {
+                tokio::task::yield_now().await;
+            }
+        }
+    }
+
+    /// Whether the next contained operation should fail as though it timed out.
+    pub(crate) fn spurious_timeout(&self) -> bool {
+        self.chaos.timeout_rate > 0.0 && self.unit() < self.chaos.timeout_rate
+    }
+}
@@ -0,0 +1,90 @@
+use std::{mem, sync::Mutex, time::Duration};
+
+use crate::{
+    time::{sleep_until, Instant},
+    Slock,
+};
+
+type Setter<T> = Box<dyn FnOnce(T) -> T + Send>;
+
+/// Collects [`Slock::set_coalesced`] setters until their window closes.
+pub(crate) struct Coalesce<T> {
+    pending: Mutex<Pending<T>>,
+    /// Held while a batch is applied, so only one caller applies each batch.
+    applying: tokio::sync::Mutex<()>,
+}
+
+struct Pending<T> {
+    setters: Vec<Setter<T>>,
+    /// Identifies the batch new setters join.
+    batch: u64,
+    /// When the open batch's window closes.
+    deadline: Option<Instant>,
+}
+
+impl<T> Default for Coalesce<T> {
+    fn default() -> Self {
+        Self {
+            pending: Mutex::new(Pending {
+                setters: Vec::new(),
+                batch: 0,
+                deadline: None,
+            }),
+            applying: Default::default(),
+        }
+    }
+}
+
+impl<T> Slock<T> {
+    /// Like [`Slock::set`], but merges every setter called within `window` of the first into a single write.
+    ///
+    /// Setters run in the order they were called, and the hook only runs once per window.
+    /// Resolves once this setter has been applied.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::time::Duration;
+    /// # async {
+    /// let cursor = Slock::new((0i32, 0i32));
+    ///
+    /// // A burst of mouse moves becomes one write
+    /// tokio::join!(
+    ///     cursor.set_coalesced(Duration::from_millis(16), |_| (1, 1)),
+    ///     cursor.set_coalesced(Duration::from_millis(16), |_| (2, 3)),
+    /// );
+    /// # };
+    /// ```
+    pub async fn set_coalesced<F>(&self, window: Duration, setter: F)
+    where
+        F: FnOnce(T) -> T + Send + 'static,
+    {
+        let coalesce = &self.lock.coalesce;
+        let (batch, deadline) = {
+            let mut pending = coalesce.pending.lock().unwrap();
+            let deadline = *pending
+                .deadline
+                .get_or_insert_with(|| Instant::now() + window);
+            pending.setters.push(Box::new(setter));
+            (pending.batch, deadline)
+        };
+        sleep_until(deadline).await;
+
+        // Whoever gets here first applies the whole batch
+        let _applying = coalesce.applying.lock().await;
+        if coalesce.pending.lock().unwrap().batch != batch {
+            return;
+        }
+        self.set(|value| {
+            // Taken inside the setter, so nothing is lost if this write is cancelled first
+            let setters = {
+                let mut pending = coalesce.pending.lock().unwrap();
+                pending.batch += 1;
+                pending.deadline = None;
+                mem::take(&mut pending.setters)
+            };
+            setters
+                .into_iter()
+                .fold(value, |value, setter| setter(value))
+        })
+        .await;
+    }
+}
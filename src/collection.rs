@@ -0,0 +1,208 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::{BuildHasher, Hash},
+};
+
+use crate::Slock;
+
+/// A collection that can be held in a Slock, so generic code can work on any `Slock`-wrapped collection.
+///
+/// Implemented for `Vec`, `VecDeque`, `HashMap` and `HashSet` holding `'static` elements.
+/// ```rust
+/// # use slock::*;
+/// async fn reset<C: SlockCollection>(lock: &Slock<C>) {
+///     if !lock.is_empty().await {
+///         lock.clear().await;
+///     }
+/// }
+/// ```
+pub trait SlockCollection {
+    /// What [`SlockCollection::retain`] predicates are given for each element.
+    type Item<'a>
+    where
+        Self: 'a;
+
+    /// The number of elements in the collection.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the collection has no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every element.
+    fn clear(&mut self);
+
+    /// Keep only the elements matching `predicate`.
+    fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(Self::Item<'_>) -> bool;
+}
+
+impl<T: 'static> SlockCollection for Vec<T> {
+    type Item<'a>
+        = &'a T
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+
+    fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(Self::Item<'_>) -> bool,
+    {
+        Vec::retain(self, predicate)
+    }
+}
+
+impl<T: 'static> SlockCollection for VecDeque<T> {
+    type Item<'a>
+        = &'a T
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn clear(&mut self) {
+        VecDeque::clear(self)
+    }
+
+    fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(Self::Item<'_>) -> bool,
+    {
+        VecDeque::retain(self, predicate)
+    }
+}
+
+impl<K: Eq + Hash + 'static, V: 'static, S: BuildHasher + 'static> SlockCollection
+    for HashMap<K, V, S>
+{
+    type Item<'a>
+        = (&'a K, &'a V)
+    where
+        Self: 'a;
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self)
+    }
+
+    fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(Self::Item<'_>) -> bool,
+    {
+        HashMap::retain(self, |key, value| predicate((key, value)))
+    }
+}
+
+impl<T: Eq + Hash + 'static, S: BuildHasher + 'static> SlockCollection for HashSet<T, S> {
+    type Item<'a>
+        = &'a T
+    where
+        Self: 'a;
+
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+
+    fn clear(&mut self) {
+        HashSet::clear(self)
+    }
+
+    fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(Self::Item<'_>) -> bool,
+    {
+        HashSet::retain(self, predicate)
+    }
+}
+
+impl<C: SlockCollection> Slock<C> {
+    /// The number of elements in the collection.
+    pub async fn len(&self) -> usize {
+        let data = self.read().await;
+        data.value.len()
+    }
+
+    /// Returns `true` if the collection has no elements.
+    pub async fn is_empty(&self) -> bool {
+        let data = self.read().await;
+        data.value.is_empty()
+    }
+
+    /// Remove every element.
+    pub async fn clear(&self) {
+        self.set(|mut collection| {
+            collection.clear();
+            collection
+        })
+        .await;
+    }
+
+    /// Keep only the elements matching `predicate`, returning the number removed.
+    pub async fn retain<F>(&self, predicate: F) -> usize
+    where
+        F: FnMut(C::Item<'_>) -> bool,
+    {
+        let mut removed = 0;
+        self.set(|mut collection| {
+            let before = collection.len();
+            collection.retain(predicate);
+            removed = before - collection.len();
+            collection
+        })
+        .await;
+        removed
+    }
+
+    /// Add every item from `iter`, which is collected before the lock is taken.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let rows = Slock::new(Vec::new());
+    /// rows.extend_from_iter((0..100_000).map(|i| i * 2)).await;
+    /// # };
+    /// ```
+    pub async fn extend_from_iter<I>(&self, iter: I)
+    where
+        I: IntoIterator,
+        C: Extend<I::Item>,
+    {
+        let items: Vec<I::Item> = iter.into_iter().collect();
+        self.set(|mut collection| {
+            collection.extend(items);
+            collection
+        })
+        .await;
+    }
+
+    /// Replace the collection with one built from `iter`.
+    ///
+    /// The new collection is built before the lock is taken, and the old one is dropped after it is released.
+    pub async fn replace_from_iter<I>(&self, iter: I)
+    where
+        I: IntoIterator,
+        C: FromIterator<I::Item>,
+    {
+        let new: C = iter.into_iter().collect();
+        let mut old = None;
+        self.set(|collection| {
+            old = Some(collection);
+            new
+        })
+        .await;
+        drop(old);
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::{Arc, Mutex};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{Error, Slock};
+
+/// How a [`CompressedSlock`] compresses its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Codec {
+    /// Zstandard at its default level.
+    Zstd,
+}
+
+impl Codec {
+    fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, Error> {
+        let json = serde_json::to_vec(value).map_err(backend)?;
+        match self {
+            Codec::Zstd => zstd::encode_all(&json[..], 0).map_err(backend),
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, Error> {
+        let json = match self {
+            Codec::Zstd => zstd::decode_all(bytes).map_err(backend)?,
+        };
+        serde_json::from_slice(&json).map_err(backend)
+    }
+}
+
+/// A decoded value, along with the version it was decoded from.
+type Decoded<T> = (u64, Arc<T>);
+
+/// A lock holding its value serialized and compressed, created by [`Slock::new_compressed`].
+///
+/// The value is decompressed on access. The most recently decoded value is cached,
+/// so repeated reads between writes only decompress once.
+pub struct CompressedSlock<T> {
+    lock: Slock<Vec<u8>>,
+    codec: Codec,
+    /// The last decoded value.
+    cache: Arc<Mutex<Option<Decoded<T>>>>,
+}
+
+impl<T: Serialize + DeserializeOwned> Slock<T> {
+    /// Create a lock that keeps its value compressed in memory.
+    ///
+    /// Suited to large, rarely read values, such as historical snapshots.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let history = Slock::new_compressed(vec![0u32; 100_000], Codec::Zstd).unwrap();
+    /// assert!(history.compressed_len().await < 1000);
+    ///
+    /// history.set(|mut v| { v.push(1); v }).await.unwrap();
+    /// assert_eq!(history.map(|v| v.len()).await.unwrap(), 100_001);
+    /// # };
+    /// ```
+    pub fn new_compressed(value: T, codec: Codec) -> Result<CompressedSlock<T>, Error> {
+        Ok(CompressedSlock {
+            lock: Slock::new(codec.encode(&value)?),
+            codec,
+            cache: Arc::new(Mutex::new(None)),
+        })
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> CompressedSlock<T> {
+    /// The lock holding the compressed bytes, for hooks and change notifications.
+    pub fn lock(&self) -> &Slock<Vec<u8>> {
+        &self.lock
+    }
+
+    /// The number of bytes the compressed value takes up.
+    pub async fn compressed_len(&self) -> usize {
+        self.lock.read().await.value.len()
+    }
+
+    /// Returns the current value, decompressing it unless it's already cached.
+    async fn decoded(&self) -> Result<Arc<T>, Error> {
+        let data = self.lock.read().await;
+        let mut cache = self.cache.lock().unwrap();
+        if let Some((version, value)) = cache.as_ref() {
+            if *version == data.version {
+                return Ok(value.clone());
+            }
+        }
+        let value: Arc<T> = Arc::new(self.codec.decode(&data.value)?);
+        *cache = Some((data.version, value.clone()));
+        Ok(value)
+    }
+
+    /// Extract inner values from the decompressed value.
+    ///
+    /// Returns [`Error::Backend`] if the value couldn't be decompressed.
+    pub async fn map<F, U>(&self, mapper: F) -> Result<U, Error>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        Ok(mapper(&*self.decoded().await?))
+    }
+
+    /// Change the value, recompressing it under the write lock.
+    ///
+    /// Returns [`Error::Backend`] and leaves the value unchanged if it couldn't be decompressed or recompressed.
+    pub async fn set<F>(&self, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        let codec = self.codec;
+        let (result, _) = self
+            .lock
+            .update(|bytes| {
+                match codec
+                    .decode(bytes)
+                    .and_then(|value| codec.encode(&setter(value)))
+                {
+                    Ok(bytes) => (Some(bytes), Ok(())),
+                    Err(e) => (None, Err(e)),
+                }
+            })
+            .await?;
+        result
+    }
+}
+
+impl<T: Clone + Serialize + DeserializeOwned> CompressedSlock<T> {
+    /// Decompress a copy of the value.
+    pub async fn get_clone(&self) -> Result<T, Error> {
+        Ok((*self.decoded().await?).clone())
+    }
+}
+
+impl<T> Clone for CompressedSlock<T> {
+    fn clone(&self) -> Self {
+        Self {
+            lock: self.lock.clone(),
+            codec: self.codec,
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+fn backend(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Error {
+    Error::Backend(e.into())
+}
@@ -0,0 +1,61 @@
+use std::{sync::RwLock, time::Duration};
+
+/// Defaults for every lock created after [`configure`] is called.
+///
+/// Each setting can still be overridden per lock with the matching [`SlockBuilder`](crate::SlockBuilder) method.
+/// Start from the current settings, or from `Default::default()`, and change only the ones you need,
+/// so settings added later keep their defaults.
+///
+/// There is deliberately no global hook strategy. Running a hook away from its lock needs a copy of every new value,
+/// which only `Clone + Send + 'static` values can give, while [`Slock::new`](crate::Slock::new) accepts any value.
+/// Choose the strategy per lock with `SlockBuilder::hook_strategy` instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GlobalConfig {
+    /// How long closures may run while a lock is held. Defaults to one second.
+    pub default_timeout: Duration,
+    /// Whether locks count acquisitions, wait time and timeouts. Defaults to `true`.
+    pub metrics_enabled: bool,
+}
+
+impl GlobalConfig {
+    const DEFAULT: Self = GlobalConfig {
+        default_timeout: Duration::from_secs(1),
+        metrics_enabled: true,
+    };
+
+    /// Set [`GlobalConfig::default_timeout`].
+    pub fn default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = timeout;
+        self
+    }
+
+    /// Set [`GlobalConfig::metrics_enabled`].
+    pub fn metrics_enabled(mut self, enabled: bool) -> Self {
+        self.metrics_enabled = enabled;
+        self
+    }
+}
+
+impl Default for GlobalConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static CONFIG: RwLock<GlobalConfig> = RwLock::new(GlobalConfig::DEFAULT);
+
+/// Change the defaults for every lock created from now on. Existing locks keep their settings.
+/// ```rust
+/// # use slock::*;
+/// # use std::time::Duration;
+/// slock::configure(slock::config().default_timeout(Duration::from_secs(5)));
+/// ```
+pub fn configure(config: GlobalConfig) {
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+/// The defaults currently given to new locks.
+pub fn config() -> GlobalConfig {
+    CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use crate::Slock;
+
+/// ## Copy-on-write
+///
+/// A `Slock<Arc<T>>` can hand out snapshots that share their data with the original lock.
+/// The data is only copied when one of the locks modifies it with [`Slock::set_cow`].
+impl<T> Slock<Arc<T>> {
+    /// Create a lock that stores its value behind an `Arc`.
+    ///
+    /// [`Slock::get_clone`] then only bumps a reference count instead of copying the value,
+    /// which suits large values that are read far more often than they're written.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let catalog = Slock::new_shared(vec![String::from("lamp"); 10_000]);
+    ///
+    /// // No strings are copied
+    /// let snapshot = catalog.get_clone().await;
+    ///
+    /// // Copied once here, since the snapshot still shares the old value
+    /// catalog.set_cow(|v| v.push(String::from("desk"))).await;
+    /// assert_eq!(snapshot.len(), 10_000);
+    /// # };
+    /// ```
+    pub fn new_shared(value: T) -> Self {
+        Slock::new(Arc::new(value))
+    }
+}
+
+impl<T: Clone> Slock<Arc<T>> {
+    /// Create a new, independent lock sharing this lock's data.
+    ///
+    /// Unlike [`Slock::clone_deep`], nothing is copied until either lock is modified.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::sync::Arc;
+    /// # async {
+    /// let world = Slock::new(Arc::new(vec![0u8; 1_000_000]));
+    ///
+    /// // Cheap, no bytes are copied
+    /// let snapshot = world.clone_cow().await;
+    ///
+    /// // The original gets its own copy here, the snapshot is untouched
+    /// world.set_cow(|v| v[0] = 1).await;
+    /// # };
+    /// ```
+    pub async fn clone_cow(&self) -> Self {
+        Slock::new(self.peek(Arc::clone).await)
+    }
+
+    /// Modify the data in place, copying it first only if it is shared with another lock.
+    pub async fn set_cow<F>(&self, setter: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        self.set(|mut v| {
+            setter(Arc::make_mut(&mut v));
+            v
+        })
+        .await;
+    }
+}
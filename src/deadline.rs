@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+use tokio::sync::SemaphorePermit;
+
+use crate::{time::timeout, Error, Slock, WriteGuard};
+
+impl<T> Slock<T> {
+    /// Like [`Slock::try_set`], but with a budget covering both waiting for the lock and running the setter.
+    ///
+    /// Returns [`Error::Timeout`] without running the setter if the deadline passes before the lock is acquired.
+    /// A setter can't be interrupted once started, so one that finishes after the deadline still has its value stored,
+    /// and is counted in [`Metrics::overruns`](crate::Metrics::overruns).
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::time::{Duration, Instant};
+    /// # async {
+    /// let cache = Slock::new(Vec::new());
+    /// let deadline = Instant::now() + Duration::from_millis(50);
+    ///
+    /// match cache.set_with_deadline(deadline, |mut v| { v.push(1); v }).await {
+    ///     Err(Error::Timeout) => println!("too busy, skipping"),
+    ///     result => result.unwrap(),
+    /// }
+    /// # };
+    /// ```
+    pub async fn set_with_deadline<F>(&self, deadline: Instant, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        let (_queued, mut data) = self.write_by(deadline).await?;
+        // The lock may have been free, but only after the deadline
+        if Instant::now() >= deadline {
+            self.lock.counters.timed_out();
+            return Err(Error::Timeout);
+        }
+
+        let result = self.apply(&mut data, setter).await;
+        if Instant::now() > deadline {
+            self.lock.counters.overran();
+        }
+        result
+    }
+
+    /// Like [`Slock::try_set`], but gives up with [`Error::Timeout`] without running the setter
+    /// if the lock can't be acquired for writing within `limit`.
+    ///
+    /// Suited to background housekeeping, which should back off under contention rather than add to it.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::time::Duration;
+    /// # async {
+    /// let cache = Slock::new(vec![1, 2, 3]);
+    /// if cache.try_set_for(Duration::from_millis(5), |mut v| { v.clear(); v }).await.is_err() {
+    ///     println!("cache busy, clearing later");
+    /// }
+    /// # };
+    /// ```
+    pub async fn try_set_for<F>(&self, limit: Duration, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        let (_queued, mut data) = self.write_by(Instant::now() + limit).await?;
+        self.apply(&mut data, setter).await
+    }
+
+    /// Like `write_as`, also taking a place in the write queue, but gives up at `deadline`.
+    async fn write_by(
+        &self,
+        deadline: Instant,
+    ) -> Result<(Option<SemaphorePermit<'_>>, WriteGuard<'_, T>), Error> {
+        let budget = deadline.saturating_duration_since(Instant::now());
+        let acquired = timeout(budget, async {
+            let queued = self.enqueue(true).await?;
+            Ok((queued, self.try_write_as(None).await?))
+        })
+        .await;
+        match acquired {
+            Ok(acquired) => acquired,
+            Err(_) => {
+                self.lock.counters.timed_out();
+                Err(Error::Timeout)
+            }
+        }
+    }
+}
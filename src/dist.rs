@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use redis::{aio::MultiplexedConnection, AsyncCommands, Client};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use crate::{Error, Slock};
+
+/// The local cache of a [`DistSlock`]'s value.
+struct Cache<T> {
+    /// The cached value, along with the version it was read at.
+    value: Option<(u64, T)>,
+    /// The newest version another instance has announced, so a slower read of an older one isn't cached.
+    invalidated: u64,
+    /// Whether invalidations are still arriving. Nothing is cached once they stop.
+    live: bool,
+}
+
+/// A Slock whose value lives in Redis, shared by every instance of an application.
+///
+/// Values are stored as JSON in a hash at `key`, alongside a version that every write increments.
+/// Writes are optimistic: the hash is `WATCH`ed while the setter runs, and the write is retried if another
+/// instance changed it first.
+/// Reads are served from a local cache, which is invalidated over pub/sub whenever another instance writes.
+/// If the pub/sub connection is lost, every read goes to Redis instead.
+/// ```rust,no_run
+/// # use slock::*;
+/// # async {
+/// let client = redis::Client::open("redis://127.0.0.1/").unwrap();
+/// let visits = DistSlock::connect(&client, "visits", 0u64).await.unwrap();
+/// visits.set(|v| v + 1).await.unwrap();
+/// # };
+/// ```
+pub struct DistSlock<T> {
+    key: Arc<str>,
+    cache: Slock<Cache<T>>,
+    connection: MultiplexedConnection,
+    /// `WATCH` applies to a whole connection, so writes take turns on their own.
+    writes: Arc<Mutex<MultiplexedConnection>>,
+    _invalidator: Arc<Invalidator>,
+}
+
+impl<T> DistSlock<T>
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    /// Connect to the value at `key`, storing `initial` if it doesn't exist yet.
+    pub async fn connect(client: &Client, key: &str, initial: T) -> Result<Self, Error> {
+        let mut connection = client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(backend)?;
+        let writes = client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(backend)?;
+        let _: bool = connection
+            .hset_nx(key, "value", encode(&initial)?)
+            .await
+            .map_err(backend)?;
+        let _: bool = connection
+            .hset_nx(key, "version", 0)
+            .await
+            .map_err(backend)?;
+
+        let cache = Slock::new(Cache {
+            value: None,
+            invalidated: 0,
+            live: true,
+        });
+        let mut pubsub = client.get_async_pubsub().await.map_err(backend)?;
+        pubsub.subscribe(channel(key)).await.map_err(backend)?;
+        let stale = cache.clone();
+        let task = tokio::spawn(async move {
+            let mut messages = pubsub.into_on_message();
+            while let Some(message) = messages.next().await {
+                let version: Option<u64> = message.get_payload().ok();
+                stale
+                    .set(|mut cache: Cache<T>| {
+                        let Some(version) = version else {
+                            // Not a version we understand, so the cached value may be stale
+                            cache.value = None;
+                            return cache;
+                        };
+                        cache.invalidated = cache.invalidated.max(version);
+                        cache.value = cache.value.filter(|(seen, _)| *seen >= version);
+                        cache
+                    })
+                    .await;
+            }
+            // Without invalidations a cached value could go stale unnoticed
+            stale
+                .set(|cache| Cache {
+                    value: None,
+                    live: false,
+                    ..cache
+                })
+                .await;
+        });
+
+        Ok(Self {
+            key: key.into(),
+            cache,
+            connection,
+            writes: Arc::new(Mutex::new(writes)),
+            _invalidator: Arc::new(Invalidator(task)),
+        })
+    }
+
+    /// Returns a clone of the value, from the local cache if it is up to date.
+    pub async fn get_clone(&self) -> Result<T, Error> {
+        let cached = self.cache.map(|cache| cache.value.clone()).await;
+        if let Ok(Some((_, value))) = cached {
+            return Ok(value);
+        }
+        let mut connection = self.connection.clone();
+        let (value, version): (T, u64) = fetch(&mut connection, &self.key).await?;
+        self.remember(version, value.clone()).await;
+        Ok(value)
+    }
+
+    /// Extract inner values from the value.
+    pub async fn map<F, U>(&self, mapper: F) -> Result<U, Error>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        Ok(mapper(&self.get_clone().await?))
+    }
+
+    /// A setter for changing the value everywhere.
+    ///
+    /// Unlike [`Slock::set`], the setter runs again whenever another instance writes first,
+    /// so it may run more than once.
+    pub async fn set<F>(&self, mut setter: F) -> Result<(), Error>
+    where
+        F: FnMut(T) -> T,
+    {
+        let mut connection = self.writes.lock().await;
+        let (new, version) = loop {
+            redis::cmd("WATCH")
+                .arg(&*self.key)
+                .exec_async(&mut *connection)
+                .await
+                .map_err(backend)?;
+            let (old, _): (T, u64) = fetch(&mut connection, &self.key).await?;
+            let new = setter(old);
+            let committed: Option<(u64,)> = redis::pipe()
+                .atomic()
+                .hset(&*self.key, "value", encode(&new)?)
+                .ignore()
+                .hincr(&*self.key, "version", 1)
+                .query_async(&mut *connection)
+                .await
+                .map_err(backend)?;
+            if let Some((version,)) = committed {
+                break (new, version);
+            }
+        };
+        self.remember(version, new).await;
+        let _: usize = connection
+            .publish(channel(&self.key), version)
+            .await
+            .map_err(backend)?;
+        Ok(())
+    }
+
+    /// Caches a value unless a newer one is already cached, or has been announced.
+    async fn remember(&self, version: u64, value: T) {
+        self.cache
+            .set(|mut cache| {
+                let newer = matches!(&cache.value, Some((seen, _)) if *seen > version);
+                if cache.live && !newer && version >= cache.invalidated {
+                    cache.value = Some((version, value));
+                }
+                cache
+            })
+            .await;
+    }
+}
+
+impl<T> Clone for DistSlock<T> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            cache: self.cache.clone(),
+            connection: self.connection.clone(),
+            writes: self.writes.clone(),
+            _invalidator: self._invalidator.clone(),
+        }
+    }
+}
+
+/// Stops listening for invalidations once the last handle is dropped.
+struct Invalidator(JoinHandle<()>);
+
+impl Drop for Invalidator {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+fn channel(key: &str) -> String {
+    format!("{key}:changed")
+}
+
+async fn fetch<T: DeserializeOwned>(
+    connection: &mut MultiplexedConnection,
+    key: &str,
+) -> Result<(T, u64), Error> {
+    let (value, version): (String, u64) = redis::cmd("HMGET")
+        .arg(key)
+        .arg("value")
+        .arg("version")
+        .query_async(connection)
+        .await
+        .map_err(backend)?;
+    Ok((serde_json::from_str(&value).map_err(backend)?, version))
+}
+
+fn encode<T: Serialize>(value: &T) -> Result<String, Error> {
+    serde_json::to_string(value).map_err(backend)
+}
+
+fn backend(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Error {
+    Error::Backend(e.into())
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::{Error, Slock};
+
+/// A double-buffered lock, created by [`Slock::new_buffered`].
+///
+/// Writes go to a back buffer behind an ordinary lock. Reads go to the front buffer without locking or waiting,
+/// and only see the back buffer's changes once [`BufferedSlock::swap_buffers`] publishes them.
+/// Suited to rendering and audio threads, which can't wait for a writer.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let mix = Slock::new_buffered(vec![1.0f32, 0.5]);
+/// mix.set(|mut volumes| { volumes[1] = 0.8; volumes }).await;
+/// assert_eq!(mix.read(|volumes| volumes[1]), 0.5);
+///
+/// mix.swap_buffers().await;
+/// assert_eq!(mix.read(|volumes| volumes[1]), 0.8);
+/// # };
+/// ```
+pub struct BufferedSlock<T> {
+    front: Arc<ArcSwap<T>>,
+    back: Slock<T>,
+}
+
+impl<T: Clone> Slock<T> {
+    /// Create a double-buffered lock, with both buffers starting as `value`.
+    pub fn new_buffered(value: T) -> BufferedSlock<T> {
+        BufferedSlock {
+            front: Arc::new(ArcSwap::from_pointee(value.clone())),
+            back: Slock::new(value),
+        }
+    }
+}
+
+impl<T> BufferedSlock<T> {
+    /// The lock holding the back buffer, which writes go to.
+    pub fn back(&self) -> &Slock<T> {
+        &self.back
+    }
+
+    /// Extract inner values from the front buffer, without waiting.
+    pub fn read<F, U>(&self, reader: F) -> U
+    where
+        F: FnOnce(&T) -> U,
+    {
+        reader(&self.front.load())
+    }
+
+    /// The front buffer, which stays the same for as long as it's held even if the buffers are swapped.
+    pub fn front(&self) -> Arc<T> {
+        self.front.load_full()
+    }
+
+    /// Change the back buffer. Readers won't see it until the next swap.
+    pub async fn set<F>(&self, setter: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        self.back.set(setter).await;
+    }
+
+    /// Like [`BufferedSlock::set`], but returns any error from [`Slock::try_set`].
+    pub async fn try_set<F>(&self, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        self.back.try_set(setter).await
+    }
+}
+
+impl<T: Clone> BufferedSlock<T> {
+    /// Publish a copy of the back buffer as the new front buffer.
+    ///
+    /// Readers part way through a read finish it with the old front buffer.
+    pub async fn swap_buffers(&self) {
+        let back = self.back.get_clone().await;
+        self.front.store(Arc::new(back));
+    }
+}
+
+impl<T> Clone for BufferedSlock<T> {
+    fn clone(&self) -> Self {
+        Self {
+            front: self.front.clone(),
+            back: self.back.clone(),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::{error, fmt, time::Duration};
+
+use crate::{time::Elapsed, ValidationError};
+
+/// Errors returned by fallible lock operations.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// User code ran for longer than the lock's timeout while the lock was held.
+    Timeout,
+    /// A read timed out, most likely waiting for a writer to release the lock.
+    Stalled {
+        /// The lock's name, if it was given one with [`SlockBuilder::named`](crate::SlockBuilder::named).
+        name: Option<String>,
+        /// How long the writer had held the lock for, if it was still holding it.
+        ///
+        /// Only measured with the `time` feature, and while metrics are enabled.
+        held_for: Option<Duration>,
+        /// The label of the call holding the write lock, if it was made through [`Slock::instrument`](crate::Slock::instrument).
+        writer: Option<&'static str>,
+    },
+    /// A setter panicked on a lock with no backup of its value to restore, see [`Slock::is_poisoned`](crate::Slock::is_poisoned).
+    Poisoned,
+    /// The lock's validator rejected a new value.
+    Validation(ValidationError),
+    /// The lock's write queue was full.
+    QueueFull,
+    /// A state machine wasn't in a state it could transition from.
+    InvalidTransition,
+    /// A lock was written to after a [`ReadToken`](crate::ReadToken) for it was taken.
+    Conflict,
+    /// A closure passed to the lock panicked.
+    ClosurePanicked,
+    /// A remote store, or encoding a value for it, failed.
+    Backend(Box<dyn error::Error + Send + Sync>),
+    /// A [`CircuitBreaker`](crate::util::CircuitBreaker) was open, so the call wasn't made.
+    CircuitOpen,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Timeout => f.write_str("lock operation timed out"),
+            Error::Stalled {
+                name,
+                held_for,
+                writer,
+            } => {
+                f.write_str("lock ")?;
+                if let Some(name) = name {
+                    write!(f, "`{name}` ")?;
+                }
+                f.write_str("timed out waiting to be read")?;
+                if let Some(held_for) = held_for {
+                    write!(f, ", write locked for {held_for:?}")?;
+                }
+                match writer {
+                    Some(writer) => write!(f, " by `{writer}`"),
+                    None => Ok(()),
+                }
+            }
+            Error::Poisoned => f.write_str("lock was poisoned by a panicking setter"),
+            Error::Validation(e) => e.fmt(f),
+            Error::QueueFull => f.write_str("lock's write queue is full"),
+            Error::InvalidTransition => f.write_str("invalid state transition"),
+            Error::Conflict => f.write_str("lock was written to since it was read"),
+            Error::ClosurePanicked => f.write_str("closure passed to the lock panicked"),
+            Error::Backend(e) => write!(f, "backend error: {e}"),
+            Error::CircuitOpen => f.write_str("circuit breaker is open"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Validation(e) => Some(e),
+            Error::Backend(e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Elapsed> for Error {
+    fn from(_: Elapsed) -> Self {
+        Error::Timeout
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(e: ValidationError) -> Self {
+        Error::Validation(e)
+    }
+}
@@ -0,0 +1,90 @@
+use std::{fmt, sync::RwLock, time::Duration};
+
+use crate::Error;
+
+/// A problem the crate would otherwise have no way of reporting, passed to the [`set_event_logger`] logger.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SlockEvent<'a> {
+    /// A [`Slock::set`](crate::Slock::set) failed, so its new value was dropped.
+    WriteDropped {
+        /// The lock's name, if it was given one.
+        lock: Option<&'a str>,
+        /// The call's label, if it was made through [`Slock::instrument`](crate::Slock::instrument).
+        label: Option<&'a str>,
+        /// Why the write failed.
+        error: &'a Error,
+    },
+    /// A hook panicked or timed out. The set that ran it still happened.
+    HookFailed {
+        /// The lock's name, if it was given one.
+        lock: Option<&'a str>,
+        /// The label of the set that ran the hook, if it was made through [`Slock::instrument`](crate::Slock::instrument).
+        label: Option<&'a str>,
+        /// How the hook failed.
+        error: &'a Error,
+    },
+    /// A read taken with [`Slock::read_for`](crate::Slock::read_for) was held for longer than its limit.
+    ReadOverran {
+        /// The lock's name, if it was given one.
+        lock: Option<&'a str>,
+        /// How long the read was held for.
+        held_for: Duration,
+        /// How long the read was taken for.
+        limit: Duration,
+    },
+}
+
+impl fmt::Display for SlockEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (what, lock, label, error) = match self {
+            SlockEvent::WriteDropped { lock, label, error } => {
+                ("write dropped", lock, label, error)
+            }
+            SlockEvent::HookFailed { lock, label, error } => ("hook failed", lock, label, error),
+            SlockEvent::ReadOverran {
+                lock,
+                held_for,
+                limit,
+            } => {
+                f.write_str("read overran")?;
+                if let Some(lock) = lock {
+                    write!(f, " on lock `{lock}`")?;
+                }
+                return write!(f, ": held for {held_for:?}, limit was {limit:?}");
+            }
+        };
+        f.write_str(what)?;
+        if let Some(lock) = lock {
+            write!(f, " on lock `{lock}`")?;
+        }
+        if let Some(label) = label {
+            write!(f, " by `{label}`")?;
+        }
+        write!(f, ": {error}")
+    }
+}
+
+type Logger = Box<dyn Fn(&SlockEvent) + Send + Sync>;
+
+static LOGGER: RwLock<Option<Logger>> = RwLock::new(None);
+
+/// Send every [`SlockEvent`] to `logger`, such as to forward them to `log` or `tracing`.
+///
+/// Replaces any previous logger. Events are dropped until a logger is set.
+/// ```rust
+/// # use slock::*;
+/// slock::set_event_logger(|event| eprintln!("slock: {event}"));
+/// ```
+pub fn set_event_logger<F>(logger: F)
+where
+    F: Fn(&SlockEvent) + Send + Sync + 'static,
+{
+    *LOGGER.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(logger));
+}
+
+pub(crate) fn emit(event: SlockEvent) {
+    if let Some(logger) = LOGGER.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        logger(&event);
+    }
+}
@@ -0,0 +1,94 @@
+use std::{error::Error, fmt, fs, io, path::Path};
+
+use notify::{RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use crate::Slock;
+
+/// Returned when a watched file can't be loaded.
+#[derive(Debug)]
+pub enum WatchError<E> {
+    /// The file couldn't be read.
+    Io(io::Error),
+    /// The file couldn't be watched.
+    Watch(notify::Error),
+    /// The file's contents couldn't be parsed.
+    Parse(E),
+}
+
+impl<E: fmt::Display> fmt::Display for WatchError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchError::Io(e) => write!(f, "failed to read watched file: {}", e),
+            WatchError::Watch(e) => write!(f, "failed to watch file: {}", e),
+            WatchError::Parse(e) => write!(f, "failed to parse watched file: {}", e),
+        }
+    }
+}
+
+impl<E: Error> Error for WatchError<E> {}
+
+impl<T: Send + Sync + 'static> Slock<T> {
+    /// Create a lock holding the parsed contents of a file, which is reloaded whenever the file changes.
+    ///
+    /// Reloads go through `set`, so hooks fire as usual.
+    /// If a reload can't be read or parsed, the lock keeps its previous value.
+    /// Watching stops at the first change after every handle to the lock has been dropped.
+    /// ```rust,no_run
+    /// # use slock::*;
+    /// # async {
+    /// let config = Slock::from_file_watch("config.toml", |text| text.parse::<String>()).unwrap();
+    /// config.hook(|v| println!("config reloaded: {}", v)).await;
+    /// # };
+    /// ```
+    pub fn from_file_watch<P, F, E>(path: P, parse: F) -> Result<Self, WatchError<E>>
+    where
+        P: AsRef<Path>,
+        F: Fn(&str) -> Result<T, E> + Send + 'static,
+    {
+        let path = fs::canonicalize(path).map_err(WatchError::Io)?;
+        let lock = Slock::new(load(&path, &parse)?);
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            sender.send(event).ok();
+        })
+        .map_err(WatchError::Watch)?;
+        // Editors often replace files rather than writing to them, so watch the whole directory.
+        let directory = path.parent().unwrap_or(&path);
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(WatchError::Watch)?;
+
+        let watched = lock.clone();
+        tokio::spawn(async move {
+            let _watcher = watcher;
+            while let Some(event) = receiver.recv().await {
+                if !watched.has_other_refs() {
+                    break;
+                }
+                let touched = matches!(
+                    &event,
+                    Ok(event) if !event.kind.is_access() && event.paths.contains(&path)
+                );
+                if !touched {
+                    continue;
+                }
+                let Ok(value) = load(&path, &parse) else {
+                    continue;
+                };
+                watched.set(|_| value).await;
+            }
+        });
+
+        Ok(lock)
+    }
+}
+
+fn load<T, F, E>(path: &Path, parse: &F) -> Result<T, WatchError<E>>
+where
+    F: Fn(&str) -> Result<T, E>,
+{
+    let text = fs::read_to_string(path).map_err(WatchError::Io)?;
+    parse(&text).map_err(WatchError::Parse)
+}
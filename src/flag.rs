@@ -0,0 +1,41 @@
+use crate::Slock;
+
+impl Slock<bool> {
+    /// Set the flag to `true`.
+    pub async fn set_true(&self) {
+        self.set(|_| true).await;
+    }
+
+    /// Set the flag to `false`.
+    pub async fn set_false(&self) {
+        self.set(|_| false).await;
+    }
+
+    /// Flip the flag, returning its new value.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let paused = Slock::new(false);
+    /// assert!(paused.toggle().await);
+    /// assert!(!paused.toggle().await);
+    /// # };
+    /// ```
+    pub async fn toggle(&self) -> bool {
+        let (old, toggled) = self.update_internal(|v| (Some(!v), *v)).await;
+        old != toggled
+    }
+
+    /// Wait until the flag is `true`.
+    ///
+    /// Resolves immediately if it already is.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let shutdown = Slock::new(false);
+    /// tokio::join!(shutdown.wait_until_true(), shutdown.set_true());
+    /// # };
+    /// ```
+    pub async fn wait_until_true(&self) {
+        self.wait_until(|v| *v).await;
+    }
+}
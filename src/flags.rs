@@ -0,0 +1,88 @@
+//! Runtime feature flags, initialized from environment variables.
+//!
+//! ```rust
+//! use slock::flags::FlagSlock;
+//!
+//! # async {
+//! let beta = FlagSlock::from_env("MY_APP_BETA");
+//! if beta.is_enabled().await {
+//!     // ...
+//! }
+//!
+//! // Override at runtime
+//! beta.set_true().await;
+//! # };
+//! ```
+
+use std::{env, ops::Deref, sync::Arc};
+
+use crate::Slock;
+
+/// A shared on/off flag whose initial state comes from an environment variable.
+///
+/// Derefs to a `Slock<bool>`, so it can be overridden, toggled, hooked and waited on like any other flag.
+pub struct FlagSlock {
+    name: Arc<str>,
+    lock: Slock<bool>,
+}
+
+impl FlagSlock {
+    /// Create a flag from the environment variable `name`, disabled if it isn't set.
+    ///
+    /// `1`, `true`, `yes` and `on` (in any case) enable the flag, anything else disables it.
+    pub fn from_env(name: &str) -> Self {
+        Self::from_env_or(name, false)
+    }
+
+    /// Create a flag from the environment variable `name`, falling back to `default` if it isn't set.
+    pub fn from_env_or(name: &str, default: bool) -> Self {
+        Self {
+            name: name.into(),
+            lock: Slock::new(read_env(name).unwrap_or(default)),
+        }
+    }
+
+    /// The environment variable this flag was read from.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if the flag is enabled.
+    pub async fn is_enabled(&self) -> bool {
+        self.lock.get().await
+    }
+
+    /// Discard any runtime override by reading the environment variable again.
+    ///
+    /// If it has since been unset, the flag is left unchanged.
+    pub async fn reload(&self) {
+        if let Some(enabled) = read_env(&self.name) {
+            self.lock.set(|_| enabled).await;
+        }
+    }
+}
+
+impl Clone for FlagSlock {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            lock: self.lock.clone(),
+        }
+    }
+}
+
+impl Deref for FlagSlock {
+    type Target = Slock<bool>;
+
+    fn deref(&self) -> &Slock<bool> {
+        &self.lock
+    }
+}
+
+fn read_env(name: &str) -> Option<bool> {
+    let value = env::var(name).ok()?;
+    Some(matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    ))
+}
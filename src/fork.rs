@@ -0,0 +1,58 @@
+use crate::{Error, Slock};
+
+type Merge<T> = Box<dyn FnOnce(T, &T) -> T + Send>;
+
+/// How [`Slock::merge_into`] reconciles a forked lock with its parent.
+pub enum MergeStrategy<T> {
+    /// Replace the parent's value with the fork's.
+    LastWriteWins,
+    /// Combine the parent's current value with the fork's.
+    Custom(Merge<T>),
+}
+
+impl<T> MergeStrategy<T> {
+    /// A custom strategy, called with the parent's current value and the fork's value.
+    pub fn custom<F>(merge: F) -> Self
+    where
+        F: FnOnce(T, &T) -> T + Send + 'static,
+    {
+        MergeStrategy::Custom(Box::new(merge))
+    }
+}
+
+impl<T: Clone> Slock<T> {
+    /// Create an independent lock seeded with this lock's current value, for speculative changes.
+    ///
+    /// Nothing set on the fork reaches this lock until it is merged back with [`Slock::merge_into`].
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let plan = Slock::new(vec![1, 2, 3]);
+    ///
+    /// let draft = plan.fork().await;
+    /// draft.push(4).await;
+    /// draft.merge_into(&plan, MergeStrategy::custom(|mut current, draft: &Vec<i32>| {
+    ///     current.extend(draft.iter().filter(|v| **v > 3));
+    ///     current
+    /// })).await.unwrap();
+    /// # };
+    /// ```
+    pub async fn fork(&self) -> Slock<T> {
+        Slock::new(self.get_clone().await)
+    }
+
+    /// Reconcile this lock's value into `parent`.
+    ///
+    /// Returns any error from setting the parent, such as a rejection by its validator.
+    pub async fn merge_into(
+        &self,
+        parent: &Slock<T>,
+        strategy: MergeStrategy<T>,
+    ) -> Result<(), Error> {
+        let value = self.get_clone().await;
+        match strategy {
+            MergeStrategy::LastWriteWins => parent.try_set(|_| value).await,
+            MergeStrategy::Custom(merge) => parent.try_set(|current| merge(current, &value)).await,
+        }
+    }
+}
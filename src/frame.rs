@@ -0,0 +1,121 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use crate::{Error, Slock};
+
+/// A lock whose writes only become visible once per frame, created by [`Slock::new_framed`].
+///
+/// Writes are staged in a pending copy of the value, which [`FramedSlock::commit_tick`] publishes to readers.
+/// Readers always see the value as of the last committed frame, never a tick part way through.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let world = Slock::new_framed(vec![(0.0f32, 0.0f32)]);
+///
+/// world.set(|mut bodies| { bodies[0].0 += 1.0; bodies }).await;
+/// assert_eq!(world.get_clone().await, vec![(0.0, 0.0)]);
+///
+/// world.commit_tick().await;
+/// assert_eq!(world.get_clone().await, vec![(1.0, 0.0)]);
+/// # };
+/// ```
+pub struct FramedSlock<T> {
+    staged: Slock<T>,
+    visible: Slock<T>,
+    /// The staged version as of the last commit.
+    committed: Arc<AtomicU64>,
+}
+
+impl<T: Clone> Slock<T> {
+    /// Create a lock that double-buffers writes until each [`FramedSlock::commit_tick`].
+    pub fn new_framed(value: T) -> FramedSlock<T> {
+        FramedSlock {
+            staged: Slock::new(value.clone()),
+            visible: Slock::new(value),
+            committed: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+impl<T> FramedSlock<T> {
+    /// The lock holding the last committed frame, for hooks and change notifications.
+    ///
+    /// Its hooks run once per committed frame with changes.
+    pub fn visible(&self) -> &Slock<T> {
+        &self.visible
+    }
+
+    /// The lock holding the pending frame, which writes go to.
+    pub fn staged(&self) -> &Slock<T> {
+        &self.staged
+    }
+
+    /// Extract inner values from the last committed frame.
+    pub async fn map<F, U>(&self, mapper: F) -> Result<U, Error>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        self.visible.map(mapper).await
+    }
+
+    /// Change the pending frame. Readers won't see it until the next commit.
+    pub async fn set<F>(&self, setter: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        self.staged.set(setter).await;
+    }
+
+    /// Like [`FramedSlock::set`], but returns any error from [`Slock::try_set`].
+    pub async fn try_set<F>(&self, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        self.staged.try_set(setter).await
+    }
+}
+
+impl<T: Clone> FramedSlock<T> {
+    /// Returns a clone of the last committed frame.
+    pub async fn get_clone(&self) -> T {
+        self.visible.get_clone().await
+    }
+
+    /// Publish the pending frame to readers.
+    ///
+    /// Writes made while committing go to the next frame. Returns `false`, without touching readers,
+    /// if nothing was written since the last commit, or a concurrent commit already published a newer frame.
+    pub async fn commit_tick(&self) -> bool {
+        let (version, frame) = {
+            let staged = self.staged.read().await;
+            if staged.version == self.committed.load(Ordering::Acquire) {
+                return false;
+            }
+            (staged.version, staged.value.clone())
+        };
+        // Commits are ordered under the visible lock, so an older frame never replaces a newer one
+        let (published, _) = self
+            .visible
+            .update_internal(|_| {
+                if version <= self.committed.load(Ordering::Acquire) {
+                    return (None, false);
+                }
+                self.committed.store(version, Ordering::Release);
+                (Some(frame), true)
+            })
+            .await;
+        published
+    }
+}
+
+impl<T> Clone for FramedSlock<T> {
+    fn clone(&self) -> Self {
+        Self {
+            staged: self.staged.clone(),
+            visible: self.visible.clone(),
+            committed: self.committed.clone(),
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::{collections::HashMap, sync::Arc};
+
+use futures::future::{BoxFuture, FutureExt};
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+use crate::Slock;
+
+/// Recomputes a derived lock from its inputs.
+type Recompute = Box<dyn Fn() -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// A set of source locks and locks derived from them, kept consistent with each other.
+///
+/// Sets made through the graph recompute every dependent lock in dependency order,
+/// so a derived lock's hook never runs while one of its inputs is still out of date.
+/// Readers that hold [`SlockGraph::consistent`] never see an update part way through.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let graph = SlockGraph::new();
+/// let price = graph.source(10u32).await;
+/// let quantity = graph.source(3u32).await;
+/// let subtotal = graph.derive2(&price, &quantity, |p, q| p * q).await;
+/// let total = graph.derive(&subtotal, |s| s + 5).await;
+///
+/// graph.set(&quantity, |q| q + 1).await;
+/// assert_eq!(total.get().await, 45);
+/// # };
+/// ```
+pub struct SlockGraph {
+    nodes: Arc<RwLock<Nodes>>,
+}
+
+#[derive(Default)]
+struct Nodes {
+    /// Each lock's place in `nodes`, by lock id.
+    index: HashMap<usize, usize>,
+    /// Every lock in the graph. Locks can only be derived from ones already in the graph,
+    /// so this is always in dependency order.
+    nodes: Vec<Node>,
+}
+
+struct Node {
+    inputs: Vec<usize>,
+    /// `None` for sources.
+    recompute: Option<Recompute>,
+}
+
+impl Nodes {
+    /// The place of `lock`, adding it as a source if it isn't in the graph yet.
+    fn place<T>(&mut self, lock: &Slock<T>) -> usize {
+        let next = self.nodes.len();
+        let place = *self.index.entry(lock.id()).or_insert(next);
+        if place == next {
+            self.nodes.push(Node {
+                inputs: Vec::new(),
+                recompute: None,
+            });
+        }
+        place
+    }
+}
+
+/// Proof that no update is propagating through a [`SlockGraph`], returned by [`SlockGraph::consistent`].
+pub struct Consistent<'a> {
+    _nodes: RwLockReadGuard<'a, Nodes>,
+}
+
+impl SlockGraph {
+    /// Create a new, empty graph.
+    pub fn new() -> Self {
+        Self {
+            nodes: Default::default(),
+        }
+    }
+
+    /// Create a source lock in the graph. Other locks can also be used as sources directly.
+    pub async fn source<T>(&self, value: T) -> Slock<T> {
+        let lock = Slock::new(value);
+        self.nodes.write().await.place(&lock);
+        lock
+    }
+
+    /// Create a lock holding `compute` of `input`, kept up to date by sets made through the graph.
+    pub async fn derive<A, T, F>(&self, input: &Slock<A>, compute: F) -> Slock<T>
+    where
+        A: Send + Sync + 'static,
+        T: Send + Sync + 'static,
+        F: Fn(&A) -> T + Send + Sync + 'static,
+    {
+        let mut nodes = self.nodes.write().await;
+        let output = Slock::new(input.peek(&compute).await);
+        let inputs = vec![nodes.place(input)];
+
+        let compute = Arc::new(compute);
+        let (input, lock) = (input.clone(), output.clone());
+        let recompute: Recompute = Box::new(move || {
+            let (input, lock, compute) = (input.clone(), lock.clone(), compute.clone());
+            async move {
+                if let Ok(new) = input.map(|a| compute(a)).await {
+                    lock.set(|_| new).await;
+                }
+            }
+            .boxed()
+        });
+        add_derived(&mut nodes, &output, inputs, recompute);
+        output
+    }
+
+    /// Like [`SlockGraph::derive`], computed from two inputs read at the same moment.
+    pub async fn derive2<A, B, T, F>(&self, a: &Slock<A>, b: &Slock<B>, compute: F) -> Slock<T>
+    where
+        A: Send + Sync + 'static,
+        B: Send + Sync + 'static,
+        T: Send + Sync + 'static,
+        F: Fn(&A, &B) -> T + Send + Sync + 'static,
+    {
+        let mut nodes = self.nodes.write().await;
+        let output = Slock::new(a.zip(b).map(&compute).await);
+        let inputs = vec![nodes.place(a), nodes.place(b)];
+
+        let compute = Arc::new(compute);
+        let (a, b, lock) = (a.clone(), b.clone(), output.clone());
+        let recompute: Recompute = Box::new(move || {
+            let (a, b, lock, compute) = (a.clone(), b.clone(), lock.clone(), compute.clone());
+            async move {
+                let new = a.zip(&b).map(|a, b| compute(a, b)).await;
+                lock.set(|_| new).await;
+            }
+            .boxed()
+        });
+        add_derived(&mut nodes, &output, inputs, recompute);
+        output
+    }
+
+    /// Set a lock in the graph, then recompute every lock derived from it, in dependency order.
+    ///
+    /// Sets made directly on the lock, instead of through the graph, don't reach derived locks.
+    pub async fn set<T, F>(&self, lock: &Slock<T>, setter: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        let nodes = self.nodes.write().await;
+        lock.set(setter).await;
+        let Some(&changed) = nodes.index.get(&lock.id()) else {
+            return;
+        };
+        let mut dirty = vec![false; nodes.nodes.len()];
+        dirty[changed] = true;
+        for (place, node) in nodes.nodes.iter().enumerate().skip(changed + 1) {
+            let Some(recompute) = node.recompute.as_ref() else {
+                continue;
+            };
+            if node.inputs.iter().any(|&input| dirty[input]) {
+                recompute().await;
+                dirty[place] = true;
+            }
+        }
+    }
+
+    /// Wait for any update propagating through the graph to finish, and hold off new ones until the result is dropped.
+    ///
+    /// Locks read while it's held are all consistent with each other.
+    pub async fn consistent(&self) -> Consistent<'_> {
+        Consistent {
+            _nodes: self.nodes.read().await,
+        }
+    }
+}
+
+fn add_derived<T>(nodes: &mut Nodes, output: &Slock<T>, inputs: Vec<usize>, recompute: Recompute) {
+    nodes.index.insert(output.id(), nodes.nodes.len());
+    nodes.nodes.push(Node {
+        inputs,
+        recompute: Some(recompute),
+    });
+}
+
+impl Default for SlockGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for SlockGraph {
+    fn clone(&self) -> Self {
+        Self {
+            nodes: self.nodes.clone(),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use futures::stream::{self, StreamExt};
+
+use crate::{Error, Slock};
+
+/// The maximum number of members a group operation will touch at once.
+const GROUP_CONCURRENCY: usize = 16;
+
+/// ## Groups
+///
+/// Slock has built-in convenience methods for operating on every lock in a `Slock<Vec<Slock>>`.
+///
+/// Group operations run concurrently across members, at most 16 at a time.
+/// Wrap a group in a [`SlockVec`](crate::SlockVec) to get and remove members by index.
+pub type SlockGroup<T> = Slock<Vec<Slock<T>>>;
+
+impl<T> SlockGroup<T> {
+    /// Create a new, empty `Slock` powered group.
+    pub fn new_group() -> SlockGroup<T> {
+        Slock::new(Vec::new())
+    }
+
+    /// Returns a snapshot of the group's current members.
+    pub async fn members(&self) -> Vec<Slock<T>> {
+        self.peek(|members| members.clone()).await
+    }
+
+    /// Apply a setter to every member of the group.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let players = SlockGroup::new_group();
+    /// players.push(Slock::new(10i32)).await;
+    /// players.push(Slock::new(20i32)).await;
+    ///
+    /// // Heal everyone by 5
+    /// players.broadcast_set(|hp| hp + 5).await;
+    /// # };
+    /// ```
+    pub async fn broadcast_set<F>(&self, setter: F)
+    where
+        F: Fn(T) -> T,
+    {
+        let setter = &setter;
+        stream::iter(self.members().await)
+            .for_each_concurrent(GROUP_CONCURRENCY, |member| async move {
+                member.set(setter).await;
+            })
+            .await;
+    }
+
+    /// Map every member of the group, returning the results in member order.
+    pub async fn collect_map<F, U>(&self, mapper: F) -> Result<Vec<U>, Error>
+    where
+        F: Fn(&T) -> U,
+    {
+        let mapper = &mapper;
+        stream::iter(self.members().await)
+            .map(|member| async move { member.map(mapper).await })
+            .buffered(GROUP_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns `true` if any member of the group matches the predicate.
+    pub async fn any<F>(&self, predicate: F) -> Result<bool, Error>
+    where
+        F: Fn(&T) -> bool,
+    {
+        Ok(self.collect_map(predicate).await?.into_iter().any(|v| v))
+    }
+
+    /// Returns `true` if every member of the group matches the predicate.
+    ///
+    /// An empty group always returns `true`.
+    pub async fn all<F>(&self, predicate: F) -> Result<bool, Error>
+    where
+        F: Fn(&T) -> bool,
+    {
+        Ok(self.collect_map(predicate).await?.into_iter().all(|v| v))
+    }
+}
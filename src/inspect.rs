@@ -0,0 +1,66 @@
+use std::fmt::{Debug, Write};
+
+use crate::Slock;
+
+impl<T> Slock<T> {
+    /// Look at the value without producing anything, for logging and assertions.
+    ///
+    /// The inspector runs under the read lock and the lock's timeout, but unlike [`Slock::map`]
+    /// there's no result to unwrap; a timed out inspection is simply skipped.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let lock = Slock::new(vec![1, 2, 3]);
+    /// lock.inspect(|v| assert_eq!(v.len(), 3)).await;
+    /// # };
+    /// ```
+    pub async fn inspect<F>(&self, inspector: F)
+    where
+        F: FnOnce(&T),
+    {
+        self.map(inspector).await.ok();
+    }
+
+    /// Describes whether the lock is currently free, read or written, without waiting.
+    fn lock_state(&self) -> &'static str {
+        if self.lock.data.try_write().is_ok() {
+            "unlocked"
+        } else if self.lock.data.try_read().is_ok() {
+            "read locked"
+        } else {
+            "write locked"
+        }
+    }
+}
+
+impl<T: Debug> Slock<T> {
+    /// Pretty-print the value along with the lock's version, handle count and lock state.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let lock = Slock::new(7u8);
+    /// println!("{}", lock.debug_dump().await);
+    /// // Slock {
+    /// //     name: None,
+    /// //     version: 0,
+    /// //     ref_count: 1,
+    /// //     state: unlocked,
+    /// //     value: 7,
+    /// // }
+    /// # };
+    /// ```
+    pub async fn debug_dump(&self) -> String {
+        let state = self.lock_state();
+        let data = self.read().await;
+        let mut dump = String::new();
+        writeln!(dump, "Slock {{").unwrap();
+        writeln!(dump, "    name: {:?},", self.name()).unwrap();
+        writeln!(dump, "    version: {},", data.version).unwrap();
+        writeln!(dump, "    ref_count: {},", self.ref_count()).unwrap();
+        writeln!(dump, "    state: {state},").unwrap();
+        let value = format!("{:#?}", data.value).replace('\n', "\n    ");
+        writeln!(dump, "    value: {value},").unwrap();
+        dump.push('}');
+        dump
+    }
+}
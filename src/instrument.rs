@@ -0,0 +1,113 @@
+use tokio::sync::RwLockReadGuard;
+
+use crate::{
+    event::{self, SlockEvent},
+    Error, Slock, SlockData, POISONED,
+};
+
+/// A lock's calls made under one label, created by [`Slock::instrument`].
+///
+/// Each call is counted in [`Slock::callsite_metrics`] under the label, as well as in [`Slock::metrics`].
+/// While a labelled write holds the lock, the label is named in [`Error::Stalled`] and in [`SlockEvent`]s.
+pub struct Instrumented<'a, T> {
+    lock: &'a Slock<T>,
+    label: &'static str,
+}
+
+impl<T> Slock<T> {
+    /// Label the calls made through the returned handle, to tell them apart in metrics and events.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let cart = Slock::new(Vec::<u32>::new());
+    /// cart.instrument("checkout").set(|_| Vec::new()).await;
+    /// cart.instrument("add_item").set(|mut v| { v.push(7); v }).await;
+    ///
+    /// let checkout = cart.callsite_metrics()["checkout"];
+    /// println!("checkout waited {:?} for the cart", checkout.wait_time);
+    /// # };
+    /// ```
+    pub fn instrument(&self, label: &'static str) -> Instrumented<'_, T> {
+        Instrumented { lock: self, label }
+    }
+}
+
+impl<'a, T> Instrumented<'a, T> {
+    /// The lock the calls are made on.
+    pub fn lock(&self) -> &'a Slock<T> {
+        self.lock
+    }
+
+    /// The label the calls are made under.
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    async fn try_read(&self) -> Result<RwLockReadGuard<'a, SlockData<T>>, Error> {
+        let counters = &self.lock.lock.counters;
+        let started = counters.start();
+        let guard = self.lock.try_read().await;
+        counters.acquired_by(self.label, started);
+        guard
+    }
+
+    fn count_timeout<U>(&self, result: Result<U, Error>) -> Result<U, Error> {
+        if let Err(Error::Timeout) = result {
+            self.lock.lock.counters.timed_out_by(self.label);
+        }
+        result
+    }
+
+    /// Like [`Slock::map`], under the label.
+    pub async fn map<F, U>(&self, mapper: F) -> Result<U, Error>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        let v = self.try_read().await?;
+        let result = self.lock.contain(async { mapper(&v.value) }).await;
+        self.count_timeout(result)
+    }
+
+    /// Like [`Slock::set`], under the label.
+    pub async fn set<F>(&self, setter: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        if let Err(error) = self.try_set(setter).await {
+            event::emit(SlockEvent::WriteDropped {
+                lock: self.lock.name(),
+                label: Some(self.label),
+                error: &error,
+            });
+        }
+    }
+
+    /// Like [`Slock::try_set`], under the label.
+    pub async fn try_set<F>(&self, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        let counters = &self.lock.lock.counters;
+        let _queued = self.lock.enqueue(true).await?;
+        let started = counters.start();
+        let mut data = self.lock.try_write_as(None).await?;
+        counters.acquired_by(self.label, started);
+        counters.write_locked_by(self.label);
+        let result = self.lock.apply(&mut data, setter).await;
+        self.count_timeout(result)
+    }
+}
+
+impl<T: Clone> Instrumented<'_, T> {
+    /// Like [`Slock::get_clone`], under the label.
+    pub async fn get_clone(&self) -> T {
+        self.try_read().await.expect(POISONED).value.clone()
+    }
+}
+
+impl<T: Copy> Instrumented<'_, T> {
+    /// Like [`Slock::get`], under the label.
+    pub async fn get(&self) -> T {
+        self.try_read().await.expect(POISONED).value
+    }
+}
@@ -0,0 +1,42 @@
+use crate::Slock;
+
+/// A lock's identity and version at one moment, created by [`Slock::invalidation_token`].
+///
+/// Small and `Copy`, so a cache can store one beside each value it derived from a lock,
+/// and check it with [`InvalidationToken::is_stale`] instead of cloning the lock's value to compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InvalidationToken {
+    lock: u64,
+    version: u64,
+}
+
+impl<T> Slock<T> {
+    /// Capture the lock's identity and current version.
+    ///
+    /// Take the token before reading the value it guards, so a write in between makes it stale rather than going unnoticed.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let prices = Slock::new(vec![3, 1, 2]);
+    ///
+    /// let token = prices.invalidation_token().await;
+    /// let cheapest = prices.map(|v| v.iter().min().copied()).await.unwrap();
+    ///
+    /// prices.set(|mut v| { v.push(0); v }).await;
+    /// assert!(token.is_stale(&prices).await);
+    /// # };
+    /// ```
+    pub async fn invalidation_token(&self) -> InvalidationToken {
+        InvalidationToken {
+            lock: self.serial(),
+            version: self.read().await.version,
+        }
+    }
+}
+
+impl InvalidationToken {
+    /// Returns `true` if `lock` has been written to since the token was taken, or isn't the lock it was taken from.
+    pub async fn is_stale<T>(&self, lock: &Slock<T>) -> bool {
+        *self != lock.invalidation_token().await
+    }
+}
@@ -0,0 +1,246 @@
+//! Slocks shared between processes on the same host.
+//!
+//! ```rust,no_run
+//! use slock::ipc::IpcSlock;
+//!
+//! # async {
+//! // Every worker process opens the same file
+//! let jobs_done = IpcSlock::open("/dev/shm/my-app-jobs", 0u64).await.unwrap();
+//! jobs_done.set(|v| v + 1).await;
+//! # };
+//! ```
+
+use std::{
+    fs::OpenOptions,
+    io,
+    marker::PhantomData,
+    mem::size_of,
+    path::Path,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use bytemuck::Pod;
+use memmap2::MmapRaw;
+
+use crate::{
+    event::{self, SlockEvent},
+    time::{sleep, timeout},
+    Error,
+};
+
+/// Bytes before the value: the lock word, the initialization state and the version.
+///
+/// The lock word holds the pid of the process holding the lock, or zero if it is free.
+/// The state holds zero until a process starts initializing the value, then that process's pid until it is `READY`.
+const HEADER: usize = 16;
+
+const UNINITIALIZED: u32 = 0;
+/// Never a pid, since pids fit in far fewer bits.
+const READY: u32 = u32::MAX;
+
+/// How long to back off between attempts at a lock word another process holds.
+const BACKOFF: Duration = Duration::from_micros(50);
+
+/// A lock over a plain-old-data value in shared memory.
+///
+/// Every process that opens the same file shares the value.
+/// If a process dies while holding the lock, the next process to find it that way takes it over.
+/// The value may then be part written, which is still a valid `T`, since any bytes are.
+/// Otherwise acquisitions give up with [`Error::Timeout`] once the lock's timeout has passed.
+///
+/// Dead holders are only detected on Unix. Elsewhere, a lock whose holder died times out every acquisition.
+pub struct IpcSlock<T> {
+    map: Arc<MmapRaw>,
+    timeout: Duration,
+    value: PhantomData<T>,
+}
+
+impl<T: Pod> IpcSlock<T> {
+    /// Open the shared lock backed by the file at `path`, creating it with `initial` if it doesn't exist yet.
+    ///
+    /// A file on a memory-backed filesystem, such as `/dev/shm`, avoids touching the disk.
+    ///
+    /// If another process is still initializing the file, waits for up to a second for it to finish,
+    /// or takes over if it died part way through.
+    pub async fn open(path: impl AsRef<Path>, initial: T) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let len = (HEADER + size_of::<T>()) as u64;
+        if file.metadata()?.len() < len {
+            file.set_len(len)?;
+        }
+        let lock = Self {
+            map: Arc::new(MmapRaw::map_raw(&file)?),
+            timeout: Duration::from_secs(1),
+            value: PhantomData,
+        };
+
+        let state = lock.word(4);
+        let me = std::process::id();
+        let initialized = timeout(lock.timeout, async {
+            let mut expected = UNINITIALIZED;
+            loop {
+                match state.compare_exchange(expected, me, Ordering::AcqRel, Ordering::Acquire) {
+                    Ok(_) => {
+                        lock.write_value(initial);
+                        state.store(READY, Ordering::Release);
+                        return;
+                    }
+                    Err(READY) => return,
+                    // Take over from an initializer that died, or start if nobody has
+                    Err(initializer) if initializer == UNINITIALIZED || !alive(initializer) => {
+                        expected = initializer;
+                    }
+                    Err(_) => {
+                        expected = UNINITIALIZED;
+                        sleep(BACKOFF).await;
+                    }
+                }
+            }
+        });
+        match initialized.await {
+            Ok(()) => Ok(lock),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "timed out waiting for another process to initialize the shared lock",
+            )),
+        }
+    }
+
+    /// How long to wait for another process to release the lock. Defaults to one second.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Extract inner values from the shared value.
+    pub async fn map<F, U>(&self, mapper: F) -> Result<U, Error>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        let _held = self.acquire().await?;
+        Ok(mapper(&self.read_value()))
+    }
+
+    /// Returns a copy of the shared value.
+    pub async fn get(&self) -> Result<T, Error> {
+        self.map(|v| *v).await
+    }
+
+    /// A setter for changing the shared value.
+    ///
+    /// A failed write is reported to the [`set_event_logger`](crate::set_event_logger) logger, like [`Slock::set`](crate::Slock::set).
+    pub async fn set<F>(&self, setter: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        if let Err(error) = self.try_set(setter).await {
+            event::emit(SlockEvent::WriteDropped {
+                lock: None,
+                label: None,
+                error: &error,
+            });
+        }
+    }
+
+    /// Like [`IpcSlock::set`], but returns [`Error::Timeout`] if the lock couldn't be acquired.
+    pub async fn try_set<F>(&self, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        let _held = self.acquire().await?;
+        self.write_value(setter(self.read_value()));
+        self.version().fetch_add(1, Ordering::Release);
+        Ok(())
+    }
+
+    /// How many times the shared value has been set, by any process.
+    pub fn version_count(&self) -> u64 {
+        self.version().load(Ordering::Acquire)
+    }
+
+    async fn acquire(&self) -> Result<Held<'_>, Error> {
+        let word = self.word(0);
+        let me = std::process::id();
+        Ok(timeout(self.timeout, async {
+            let mut expected = 0;
+            loop {
+                match word.compare_exchange(expected, me, Ordering::Acquire, Ordering::Relaxed) {
+                    Ok(_) => return Held(word),
+                    // Take over from a holder that died without releasing the lock
+                    Err(holder) if holder == 0 || !alive(holder) => expected = holder,
+                    Err(_) => {
+                        expected = 0;
+                        sleep(BACKOFF).await;
+                    }
+                }
+            }
+        })
+        .await?)
+    }
+
+    fn word(&self, offset: usize) -> &AtomicU32 {
+        // The map is page aligned and at least `HEADER` bytes long
+        unsafe { &*(self.map.as_mut_ptr().add(offset) as *const AtomicU32) }
+    }
+
+    fn version(&self) -> &AtomicU64 {
+        unsafe { &*(self.map.as_mut_ptr().add(8) as *const AtomicU64) }
+    }
+
+    fn value_bytes(&self) -> *mut u8 {
+        unsafe { self.map.as_mut_ptr().add(HEADER) }
+    }
+
+    fn read_value(&self) -> T {
+        let bytes = unsafe { std::slice::from_raw_parts(self.value_bytes(), size_of::<T>()) };
+        bytemuck::pod_read_unaligned(bytes)
+    }
+
+    fn write_value(&self, value: T) {
+        let bytes = unsafe { std::slice::from_raw_parts_mut(self.value_bytes(), size_of::<T>()) };
+        bytes.copy_from_slice(bytemuck::bytes_of(&value));
+    }
+}
+
+impl<T> Clone for IpcSlock<T> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+            timeout: self.timeout,
+            value: PhantomData,
+        }
+    }
+}
+
+/// Releases the cross-process lock when dropped, even if the holder panics.
+struct Held<'a>(&'a AtomicU32);
+
+impl Drop for Held<'_> {
+    fn drop(&mut self) {
+        self.0.store(0, Ordering::Release);
+    }
+}
+
+/// Returns `false` if the process with `pid` has certainly exited.
+#[cfg(unix)]
+fn alive(pid: u32) -> bool {
+    // Signal zero only checks whether the process could be signalled
+    if unsafe { libc::kill(pid as libc::pid_t, 0) } == 0 {
+        return true;
+    }
+    io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
+#[cfg(not(unix))]
+fn alive(_pid: u32) -> bool {
+    true
+}
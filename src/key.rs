@@ -0,0 +1,51 @@
+/// Declare newtype keys for [`SlockMap`](crate::SlockMap)s, so maps keyed by different kinds of id can't be mixed up.
+///
+/// Each key wraps a `Copy` id and implements everything a map key needs, along with `From` the id and `Display`.
+/// ```rust
+/// # use slock::*;
+/// slock_key! {
+///     pub struct UserId(u64);
+///     pub struct GuildId(u64);
+/// }
+///
+/// # async {
+/// let sessions: SlockMap<UserId, String> = SlockMap::new_map();
+/// sessions.insert(UserId(7), |_| "bob".into()).await;
+/// let name = sessions.from_key(7.into()).await;
+/// # };
+/// ```
+///
+/// A key of the wrong kind is rejected at compile time:
+/// ```compile_fail
+/// # use slock::*;
+/// # slock_key! {
+/// #     pub struct UserId(u64);
+/// #     pub struct GuildId(u64);
+/// # }
+/// # async {
+/// let sessions: SlockMap<UserId, String> = SlockMap::new_map();
+/// sessions.from_key(GuildId(7)).await;
+/// # };
+/// ```
+#[macro_export]
+macro_rules! slock_key {
+    ($($(#[$attr:meta])* $vis:vis struct $name:ident($inner_vis:vis $t:ty);)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+            $vis struct $name($inner_vis $t);
+
+            impl ::std::convert::From<$t> for $name {
+                fn from(id: $t) -> Self {
+                    $name(id)
+                }
+            }
+
+            impl ::std::fmt::Display for $name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    ::std::fmt::Display::fmt(&self.0, f)
+                }
+            }
+        )*
+    };
+}
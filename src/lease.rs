@@ -0,0 +1,156 @@
+use std::{
+    pin::pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use futures::future;
+use tokio::sync::Notify;
+
+use crate::{
+    event::{self, SlockEvent},
+    time::{sleep_until, Instant},
+    Error, Slock,
+};
+
+/// Tracks which lease, if any, currently owns a lock's writes.
+#[derive(Default)]
+pub(crate) struct Leases {
+    current: Mutex<Option<(u64, Instant)>>,
+    released: Notify,
+    next_id: AtomicU64,
+}
+
+impl Leases {
+    /// The active lease's id and expiry, ignoring leases that have expired.
+    fn holder(&self) -> Option<(u64, Instant)> {
+        self.current
+            .lock()
+            .unwrap()
+            .filter(|(_, until)| *until > Instant::now())
+    }
+
+    /// Returns `true` if a write on behalf of `lease` may go ahead.
+    pub(crate) fn allows(&self, lease: Option<u64>) -> bool {
+        match self.holder() {
+            None => true,
+            Some((id, _)) => lease == Some(id),
+        }
+    }
+
+    /// Waits until a write on behalf of `lease` may go ahead.
+    pub(crate) async fn wait(&self, lease: Option<u64>) {
+        loop {
+            let mut released = pin!(self.released.notified());
+            released.as_mut().enable();
+            let until = match self.holder() {
+                Some((id, until)) if lease != Some(id) => until,
+                _ => return,
+            };
+            future::select(pin!(sleep_until(until)), released).await;
+        }
+    }
+
+    /// Takes the lease once it is free, returning the new lease's id.
+    async fn acquire(&self, duration: Duration) -> u64 {
+        loop {
+            self.wait(None).await;
+            let mut current = self.current.lock().unwrap();
+            if current.is_none_or(|(_, until)| until <= Instant::now()) {
+                let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                *current = Some((id, Instant::now() + duration));
+                return id;
+            }
+        }
+    }
+
+    fn release(&self, id: u64) {
+        let mut current = self.current.lock().unwrap();
+        if matches!(*current, Some((held, _)) if held == id) {
+            *current = None;
+            drop(current);
+            self.released.notify_waiters();
+        }
+    }
+}
+
+/// Exclusive rights to set a lock for a limited time, created by [`Slock::lease`].
+///
+/// The lease is released when dropped.
+pub struct Lease<T> {
+    lock: Slock<T>,
+    id: u64,
+}
+
+impl<T> Slock<T> {
+    /// Wait for any other lease to end, then take exclusive rights to set the lock for `duration`.
+    ///
+    /// Until the lease is released or expires, every other write waits for it.
+    /// Reads are unaffected.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::time::Duration;
+    /// # async {
+    /// let schedule = Slock::new(Vec::<u32>::new());
+    ///
+    /// let lease = schedule.lease(Duration::from_secs(5)).await;
+    /// lease.set(|mut v| { v.push(1); v }).await;
+    /// lease.release();
+    /// # };
+    /// ```
+    pub async fn lease(&self, duration: Duration) -> Lease<T> {
+        Lease {
+            lock: self.clone(),
+            id: self.lock.leases.acquire(duration).await,
+        }
+    }
+}
+
+impl<T> Lease<T> {
+    /// The leased lock.
+    pub fn lock(&self) -> &Slock<T> {
+        &self.lock
+    }
+
+    /// Returns `true` until the lease is released or expires.
+    pub fn is_active(&self) -> bool {
+        matches!(self.lock.lock.leases.holder(), Some((id, _)) if id == self.id)
+    }
+
+    /// Set the lock under this lease.
+    ///
+    /// Once the lease has expired, this waits for other leases like any other write.
+    /// A failed write is reported to the [`set_event_logger`](crate::set_event_logger) logger, like [`Slock::set`].
+    pub async fn set<F>(&self, setter: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        if let Err(error) = self.try_set(setter).await {
+            event::emit(SlockEvent::WriteDropped {
+                lock: self.lock.name(),
+                label: None,
+                error: &error,
+            });
+        }
+    }
+
+    /// Like [`Lease::set`], but returns any error from [`Slock::try_set`].
+    pub async fn try_set<F>(&self, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        self.lock.try_set_as(Some(self.id), setter).await
+    }
+
+    /// Give up the lease, letting other writes through.
+    pub fn release(self) {}
+}
+
+impl<T> Drop for Lease<T> {
+    fn drop(&mut self) {
+        self.lock.lock.leases.release(self.id);
+    }
+}
@@ -60,17 +60,273 @@
 //! # };
 //! ```
 
-use std::{cmp::Eq, collections::HashMap, hash::Hash, sync::Arc};
-
-use tokio::{
-    sync::RwLock,
-    time::{error::Elapsed, timeout},
+use std::{
+    future::Future,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    panic::AssertUnwindSafe,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
+use futures::FutureExt;
+
+mod any_map;
+mod arena;
+mod batch;
+#[cfg(feature = "bytes")]
+mod buffer;
+mod builder;
+mod bus;
+mod changed;
+#[cfg(feature = "rt")]
+mod channel;
+#[cfg(feature = "chaos")]
+mod chaos;
+#[cfg(feature = "time")]
+mod coalesce;
+mod collection;
+#[cfg(feature = "compress")]
+mod compress;
+mod config;
+mod cow;
+#[cfg(feature = "time")]
+mod deadline;
+#[cfg(feature = "redis")]
+mod dist;
+#[cfg(feature = "double-buffer")]
+mod double;
+mod error;
+mod event;
+#[cfg(feature = "file-watch")]
+mod file;
+mod flag;
+pub mod flags;
+mod fork;
+mod frame;
+mod graph;
+mod group;
+mod inspect;
+mod instrument;
+mod invalidate;
+#[cfg(feature = "ipc")]
+pub mod ipc;
+mod key;
+#[cfg(feature = "time")]
+mod lease;
+mod loader;
+mod machine;
+mod map;
+mod map_events;
+mod metrics;
+mod multi;
+#[cfg(feature = "rt")]
+mod notify;
+mod num;
+mod ord;
+mod partition;
+mod pause;
+mod pipe;
+mod progress;
+#[cfg(feature = "prometheus")]
+mod prometheus;
+mod queue;
+mod rcu;
+#[cfg(feature = "time")]
+mod read_lease;
+mod recompute;
+#[cfg(feature = "record")]
+mod record;
+mod result;
+#[cfg(feature = "rt")]
+mod scope;
+mod sequence;
+mod session;
+mod signal;
+mod small;
+mod statics;
+mod stream;
+mod temp_hook;
+#[cfg(feature = "time")]
+pub mod testing;
+#[cfg(all(feature = "rt", feature = "time"))]
+mod tick;
+mod time;
+mod token;
+mod tree;
+pub mod util;
+mod validate;
+mod vec;
+#[cfg(feature = "web")]
+mod web;
+
+pub use any_map::AnySlockMap;
+pub use arena::{ArenaKey, SlockArena};
+pub use batch::Batch;
+pub use builder::SlockBuilder;
+pub use bus::SlockBus;
+pub use changed::Changed;
+#[cfg(feature = "chaos")]
+pub use chaos::Chaos;
+pub use collection::SlockCollection;
+#[cfg(feature = "compress")]
+pub use compress::{Codec, CompressedSlock};
+pub use config::{config, configure, GlobalConfig};
+#[cfg(feature = "redis")]
+pub use dist::DistSlock;
+#[cfg(feature = "double-buffer")]
+pub use double::BufferedSlock;
+pub use error::Error;
+pub use event::{set_event_logger, SlockEvent};
+#[cfg(feature = "file-watch")]
+pub use file::WatchError;
+pub use fork::MergeStrategy;
+pub use frame::FramedSlock;
+pub use graph::{Consistent, SlockGraph};
+pub use group::SlockGroup;
+pub use instrument::Instrumented;
+pub use invalidate::InvalidationToken;
+#[cfg(feature = "time")]
+pub use lease::Lease;
+pub use loader::SlockLoader;
+pub use machine::SlockMachine;
+pub use map::SlockMap;
+pub use map_events::MapChange;
+pub use metrics::{CallsiteMetrics, Metrics};
+#[doc(hidden)]
+pub use multi::Joined;
+pub use multi::{eq, two_phase, Prepared, TwoPhase, Zipped};
+#[cfg(feature = "rt")]
+pub use notify::HookStrategy;
+pub use num::Overflow;
+pub use pipe::Pipe;
+pub use progress::{Progress, ProgressState};
+#[cfg(feature = "prometheus")]
+pub use prometheus::SlockCollector;
+pub use queue::WhenFull;
+#[cfg(feature = "time")]
+pub use read_lease::ReadLease;
+#[cfg(feature = "record")]
+pub use record::Frame;
+#[cfg(feature = "rt")]
+pub use scope::SlockScope;
+pub use sequence::OrderedPush;
+pub use session::Session;
+pub use signal::Signal;
+pub use small::{SmallSlock, SmallValue};
+pub use statics::StaticSlock;
+pub use stream::Lagged;
+pub use token::ReadToken;
+pub use tree::SlockTree;
+pub use validate::ValidationError;
+pub use vec::SlockVec;
+#[cfg(feature = "web")]
+pub use web::{json_route, SlockState};
+
+use tokio::sync::{watch, Notify, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use time::timeout;
+
+type Hook<T> = Box<dyn FnMut(&T) + Send + Sync>;
+
 struct SlockData<T> {
     pub version: u64,
     pub value: T,
-    pub hook: Option<Box<dyn FnMut(&T)>>,
+    pub hook: Option<Hook<T>>,
+    pub validator: Option<validate::Validator<T>>,
+    pub feed: Option<stream::Feed<T>>,
+    /// Runs the hook away from the lock, unless hooks run inline.
+    #[cfg(feature = "rt")]
+    pub notifier: Option<notify::Notifier<T>>,
+    #[cfg(feature = "record")]
+    pub history: Option<record::History<T>>,
+}
+
+impl<T> SlockData<T> {
+    fn set_hook(&mut self, hook: Hook<T>) {
+        #[cfg(feature = "rt")]
+        if let Some(notifier) = self.notifier.as_ref() {
+            notifier.set_hook(hook);
+            return;
+        }
+        self.hook = Some(hook);
+    }
+}
+
+struct SlockInner<T> {
+    /// Leaked rather than dropped once poisoned, since a panicking setter already dropped the value.
+    data: ManuallyDrop<RwLock<SlockData<T>>>,
+    /// Unique among every lock created, unlike the lock's address, which a later lock may reuse.
+    serial: u64,
+    /// Set when a setter panics on a lock with no backup to restore, see [`Slock::is_poisoned`].
+    poisoned: AtomicBool,
+    /// Publishes the version after every `set`, so tasks can wait for changes without a hook.
+    changes: watch::Sender<u64>,
+    /// Wakes [`Changed`] futures after every `set`.
+    set_notify: Notify,
+    /// Shared with tasks that report on the lock, like the watch on a read lease.
+    counters: Arc<metrics::Counters>,
+    /// How long user code may run while the lock is held.
+    timeout: Duration,
+    name: Option<String>,
+    recompute: recompute::Recompute,
+    write_queue: Option<queue::WriteQueue>,
+    pause: pause::Pause,
+    /// Subscribers to membership changes, for locks holding a `SlockMap`.
+    map_listeners: map_events::AnyListeners,
+    temp_hooks: temp_hook::TempHooks<T>,
+    #[cfg(feature = "time")]
+    coalesce: coalesce::Coalesce<T>,
+    #[cfg(feature = "time")]
+    leases: lease::Leases,
+    /// When this lock was last looked up through a `SlockMap`.
+    #[cfg(feature = "time")]
+    accessed: std::sync::Mutex<time::Instant>,
+    #[cfg(feature = "chaos")]
+    chaos: Option<chaos::Injector>,
+}
+
+impl<T> Drop for SlockInner<T> {
+    fn drop(&mut self) {
+        if !*self.poisoned.get_mut() {
+            unsafe { ManuallyDrop::drop(&mut self.data) };
+        }
+    }
+}
+
+/// The serial of the next lock to be created.
+static NEXT_SERIAL: AtomicU64 = AtomicU64::new(0);
+
+/// Why operations that can't return [`Error::Poisoned`] panic.
+const POISONED: &str = "lock was poisoned by a panicking setter";
+
+/// Write access to a lock's data, which marks the lock as no longer held when dropped.
+struct WriteGuard<'a, T> {
+    guard: RwLockWriteGuard<'a, SlockData<T>>,
+    counters: &'a metrics::Counters,
+}
+
+impl<T> Deref for WriteGuard<'_, T> {
+    type Target = SlockData<T>;
+
+    fn deref(&self) -> &SlockData<T> {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for WriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut SlockData<T> {
+        &mut self.guard
+    }
+}
+
+impl<T> Drop for WriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.counters.write_unlocked();
+    }
 }
 
 /// The [`Slock`] object.
@@ -79,22 +335,112 @@ struct SlockData<T> {
 ///
 /// When used correctly (no nesting lock access functions), deadlocks should be impossible.
 pub struct Slock<T> {
-    lock: Arc<RwLock<SlockData<T>>>,
+    lock: Arc<SlockInner<T>>,
 }
 
 impl<T> Slock<T> {
     /// Create a new Slock with a given initial value.
     pub fn new(value: T) -> Self {
-        let data = SlockData {
-            version: 0,
-            value,
-            hook: None,
+        Self::builder().build(value)
+    }
+
+    /// Returns `true` if a setter panicked on this lock with no backup of the value to restore.
+    ///
+    /// A poisoned lock has no value left. Operations that return a `Result` fail with [`Error::Poisoned`],
+    /// and the rest panic, like a poisoned [`std::sync::Mutex`] unwrapped.
+    pub fn is_poisoned(&self) -> bool {
+        self.lock.poisoned.load(Ordering::Acquire)
+    }
+
+    /// Read locks the lock, or returns [`Error::Poisoned`] if it has no value left.
+    async fn try_read(&self) -> Result<RwLockReadGuard<'_, SlockData<T>>, Error> {
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = &self.lock.chaos {
+            chaos.acquire(false).await;
+        }
+        let started = self.lock.counters.start();
+        let guard = self.lock.data.read().await;
+        self.lock.counters.acquired(started);
+        // Poisoning happens under the write lock, so the flag is settled once a guard is held
+        if self.is_poisoned() {
+            return Err(Error::Poisoned);
+        }
+        Ok(guard)
+    }
+
+    async fn read(&self) -> RwLockReadGuard<'_, SlockData<T>> {
+        self.try_read().await.expect(POISONED)
+    }
+
+    /// Write locks the lock, or returns [`Error::Poisoned`] if it has no value left.
+    async fn try_write(&self) -> Result<WriteGuard<'_, T>, Error> {
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = &self.lock.chaos {
+            chaos.acquire(true).await;
+        }
+        let started = self.lock.counters.start();
+        let guard = self.lock.data.write().await;
+        self.lock.counters.acquired(started);
+        self.lock.counters.write_locked();
+        let guard = WriteGuard {
+            guard,
+            counters: &self.lock.counters,
         };
-        Self {
-            lock: Arc::new(RwLock::new(data)),
+        if self.is_poisoned() {
+            return Err(Error::Poisoned);
+        }
+        Ok(guard)
+    }
+
+    async fn write(&self) -> WriteGuard<'_, T> {
+        self.try_write().await.expect(POISONED)
+    }
+
+    /// Like `try_write`, but first waits out paused writes and any lease other than `lease`.
+    async fn try_write_as(&self, lease: Option<u64>) -> Result<WriteGuard<'_, T>, Error> {
+        self.lock.pause.wait().await;
+        #[cfg(feature = "time")]
+        loop {
+            self.lock.leases.wait(lease).await;
+            let guard = self.try_write().await?;
+            // A lease may have been taken while we waited for the lock
+            if self.lock.leases.allows(lease) {
+                return Ok(guard);
+            }
+        }
+        #[cfg(not(feature = "time"))]
+        {
+            let _ = lease;
+            self.try_write().await
         }
     }
 
+    /// Like `write`, but first waits out paused writes and any lease other than `lease`.
+    async fn write_as(&self, lease: Option<u64>) -> WriteGuard<'_, T> {
+        self.try_write_as(lease).await.expect(POISONED)
+    }
+
+    /// Runs user code under the lock's timeout, catching any panic.
+    async fn contain<F: Future>(&self, future: F) -> Result<F::Output, Error> {
+        #[cfg(feature = "chaos")]
+        if self
+            .lock
+            .chaos
+            .as_ref()
+            .is_some_and(|c| c.spurious_timeout())
+        {
+            self.lock.counters.timed_out();
+            return Err(Error::Timeout);
+        }
+        // Nothing the user code could have broken is observed after a panic without being restored first
+        let future = AssertUnwindSafe(future).catch_unwind();
+        let result = timeout(self.lock.timeout, future).await;
+        if result.is_err() {
+            self.lock.counters.timed_out();
+        }
+        result?.map_err(|_| Error::ClosurePanicked)
+    }
+
     /// Extract inner values from within a Slock
     /// ```rust
     /// # use slock::*;
@@ -104,18 +450,51 @@ impl<T> Slock<T> {
     /// let name = lock.map(|v| v.name).await;
     /// # };
     /// ```
-    pub async fn map<F, U>(&self, mapper: F) -> Result<U, Elapsed>
+    pub async fn map<F, U>(&self, mapper: F) -> Result<U, Error>
     where
         F: FnOnce(&T) -> U,
     {
-        let v = self.lock.read().await;
-        timeout(std::time::Duration::from_secs(1), async {
-            mapper(&v.value)
-        })
-        .await
+        let v = self.try_read().await?;
+        self.contain(async { mapper(&v.value) }).await
+    }
+
+    /// Like [`Slock::map`], but moves an owned context value into the mapper.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let names = Slock::new(vec!["bob".to_string()]);
+    /// let query = String::from("bob");
+    /// let found = names.map_with(query, |v, q| v.contains(&q)).await;
+    /// # };
+    /// ```
+    pub async fn map_with<C, F, U>(&self, ctx: C, mapper: F) -> Result<U, Error>
+    where
+        F: FnOnce(&T, C) -> U,
+    {
+        self.map(|v| mapper(v, ctx)).await
+    }
+
+    /// Clone a single part of the lock's data, without cloning the rest.
+    /// ```rust
+    /// # use slock::*;
+    /// # struct User { name: String };
+    /// # let lock = Slock::new(User {name: "bobs".into()});
+    /// # async {
+    /// let name: String = lock.map_cloned(|v| &v.name).await.unwrap();
+    /// # };
+    /// ```
+    pub async fn map_cloned<F, U>(&self, projection: F) -> Result<U, Error>
+    where
+        F: FnOnce(&T) -> &U,
+        U: Clone,
+    {
+        self.map(|v| projection(v).clone()).await
     }
 
     /// A setter for changing the internal data of the lock.
+    ///
+    /// If the write fails, the new value is dropped and the failure is only reported to the [`set_event_logger`] logger.
+    /// Use [`Slock::try_set`] to handle it instead.
     /// ```rust
     /// # use slock::*;
     /// let lock = Slock::new(1i32);
@@ -129,22 +508,238 @@ impl<T> Slock<T> {
     where
         F: FnOnce(T) -> T,
     {
-        let mut data = self.lock.write().await;
-        let ptr = &mut data.value as *mut T;
-        let new = timeout(std::time::Duration::from_secs(1), async {
-            setter(unsafe { ptr.read() })
-        })
-        .await;
-        if let Ok(new) = new {
-            timeout(std::time::Duration::from_secs(1), async {
-                data.hook.as_mut().map(|hook| hook(&new));
-            })
+        if let Err(error) = self.try_set(setter).await {
+            event::emit(SlockEvent::WriteDropped {
+                lock: self.name(),
+                label: None,
+                error: &error,
+            });
+        }
+    }
+
+    /// Like [`Slock::set`], but moves an owned context value into the setter.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let log = Slock::new(Vec::<String>::new());
+    /// let line = String::from("started");
+    /// log.set_with(line, |mut v, line| {
+    ///     v.push(line);
+    ///     v
+    /// }).await;
+    /// # };
+    /// ```
+    pub async fn set_with<C, F>(&self, ctx: C, setter: F)
+    where
+        F: FnOnce(T, C) -> T,
+    {
+        self.set(|v| setter(v, ctx)).await;
+    }
+
+    /// Like [`Slock::set`], but returns [`Error::Validation`] if the lock's validator rejects the new value,
+    /// [`Error::Timeout`] if the write timed out, or [`Error::QueueFull`] if the lock's write queue is full and rejecting writes.
+    ///
+    /// A rejected value is dropped and the lock keeps its previous value.
+    /// See [`Slock::new_validated`].
+    ///
+    /// If the setter panics, returns [`Error::ClosurePanicked`] and the lock restores its previous value from the validator's backup.
+    /// A lock without a validator has no backup, so it is poisoned instead, see [`Slock::is_poisoned`].
+    /// Writes to a poisoned lock return [`Error::Poisoned`].
+    pub async fn try_set<F>(&self, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        self.try_set_as(None, setter).await
+    }
+
+    /// Like [`Slock::try_set`], but hands any error to `on_error` instead of returning it.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let balance = Slock::new_validated(10i64, |v| {
+    ///     if *v >= 0 { Ok(()) } else { Err("overdrawn") }
+    /// });
+    /// balance
+    ///     .set_or_else(|v| v - 20, |e| eprintln!("withdrawal refused: {e}"))
+    ///     .await;
+    /// # };
+    /// ```
+    pub async fn set_or_else<F, E>(&self, setter: F, on_error: E)
+    where
+        F: FnOnce(T) -> T,
+        E: FnOnce(Error),
+    {
+        if let Err(e) = self.try_set(setter).await {
+            on_error(e);
+        }
+    }
+
+    /// Like [`Slock::try_set`], on behalf of the holder of `lease`, if any.
+    async fn try_set_as<F>(&self, lease: Option<u64>, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        let _queued = self.enqueue(true).await?;
+        let mut data = self.try_write_as(lease).await?;
+        self.apply(&mut data, setter).await
+    }
+
+    /// Runs `setter` on the value and stores the result, unless the validator rejects it.
+    /// Must be called with the write lock held.
+    async fn apply<F>(&self, data: &mut SlockData<T>, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        let backup = data.validator.as_ref().map(|v| v.backup(&data.value));
+        let value = &mut data.value;
+        // The setter runs without awaiting, so a timeout means it never ran and the value is untouched
+        let new = match self
+            .contain(async { setter(unsafe { std::ptr::read(value) }) })
             .await
-            .ok();
-            unsafe { ptr.write(new) };
+        {
+            Ok(new) => new,
+            Err(Error::ClosurePanicked) => {
+                // The setter took the value with it, so put back the backup.
+                // Without one there's no value left to put back, so the lock must never be read again.
+                let Some(backup) = backup else {
+                    self.lock.poisoned.store(true, Ordering::Release);
+                    return Err(Error::ClosurePanicked);
+                };
+                unsafe { std::ptr::write(&mut data.value, backup) };
+                return Err(Error::ClosurePanicked);
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(validator) = data.validator.as_ref() {
+            if let Err(e) = validator.check(&new) {
+                let backup = backup.expect("backup is taken whenever there is a validator");
+                unsafe { std::ptr::write(&mut data.value, backup) };
+                return Err(e.into());
+            }
         }
+        unsafe { std::ptr::write(&mut data.value, new) };
+        self.publish(data).await;
+        Ok(())
+    }
+
+    /// Conditionally replaces the value under a single write acquisition.
+    ///
+    /// `updater` returns the new value, if any, along with a result for the caller.
+    /// The hook only runs and the version only changes when a new value is returned and passes validation.
+    /// Alongside the result, returns whether a new value was stored.
+    async fn update<F, R>(&self, updater: F) -> Result<(R, bool), Error>
+    where
+        F: FnOnce(&T) -> (Option<T>, R),
+    {
+        let _queued = self.enqueue(false).await?;
+        let mut data = self.try_write_as(None).await?;
+        let (new, result) = self.contain(async { updater(&data.value) }).await?;
+        Ok((result, self.store(&mut data, new).await))
+    }
 
+    /// Like `update`, for the crate's own updaters, which can't panic or run long.
+    ///
+    /// The updater runs uncontained, so it is never timed out, even spuriously by `Chaos`.
+    async fn update_internal<F, R>(&self, updater: F) -> (R, bool)
+    where
+        F: FnOnce(&T) -> (Option<T>, R),
+    {
+        // Waiting for room in the write queue never fails
+        let _queued = self.enqueue(false).await;
+        let mut data = self.write_as(None).await;
+        let (new, result) = updater(&data.value);
+        (result, self.store(&mut data, new).await)
+    }
+
+    /// Stores and publishes `new`, if there is one and it passes validation, returning whether it was stored.
+    async fn store(&self, data: &mut SlockData<T>, new: Option<T>) -> bool {
+        let Some(new) = new else {
+            return false;
+        };
+        if let Some(validator) = data.validator.as_ref() {
+            if validator.check(&new).is_err() {
+                return false;
+            }
+        }
+        data.value = new;
+        self.publish(data).await;
+        true
+    }
+
+    /// Like `map`, for the crate's own mappers, which can't panic or run long.
+    ///
+    /// The mapper runs uncontained, so it is never timed out, even spuriously by `Chaos`.
+    async fn peek<F, U>(&self, mapper: F) -> U
+    where
+        F: FnOnce(&T) -> U,
+    {
+        mapper(&self.read().await.value)
+    }
+
+    /// Runs the hook and publishes a new version.
+    /// Must be called with the write lock held, after the new value is stored.
+    async fn publish(&self, data: &mut SlockData<T>) {
+        #[cfg(feature = "rt")]
+        if let Some(notifier) = data.notifier.as_mut() {
+            notifier.notify(&data.value);
+        }
+        let hooked = self.contain(async {
+            if let Some(hook) = data.hook.as_mut() {
+                hook(&data.value);
+            }
+            self.lock.temp_hooks.call(&data.value);
+        });
+        if let Err(error) = hooked.await {
+            event::emit(SlockEvent::HookFailed {
+                lock: self.name(),
+                label: self.lock.counters.writer(),
+                error: &error,
+            });
+        }
         data.version += 1;
+        if let Some(feed) = data.feed.as_ref() {
+            feed.send(data.version, &data.value);
+        }
+        #[cfg(feature = "record")]
+        if let Some(history) = data.history.as_mut() {
+            history.push(data.version, &data.value);
+        }
+        self.lock.changes.send_replace(data.version);
+        self.lock.set_notify.notify_waiters();
+    }
+
+    /// Wait until the next time the lock is set.
+    ///
+    /// Only sets that happen after this is called are observed. The returned [`Changed`] future doesn't allocate.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let lock = Slock::new(0i32);
+    ///
+    /// // Resolves once the set below has happened
+    /// tokio::join!(lock.changed(), lock.set(|v| v + 1));
+    /// # };
+    /// ```
+    pub fn changed(&self) -> Changed<'_, T> {
+        Changed::new(self)
+    }
+
+    /// Wait until the lock's value matches a predicate.
+    ///
+    /// Resolves immediately if the value already matches.
+    pub async fn wait_until<F>(&self, mut predicate: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut receiver = self.lock.changes.subscribe();
+        loop {
+            let data = self.read().await;
+            if predicate(&data.value) {
+                return;
+            }
+            drop(data);
+            receiver.changed().await.ok();
+        }
     }
 
     /// Create's a new lock pointing to the same data.
@@ -162,15 +757,138 @@ impl<T> Slock<T> {
         }
     }
 
+    /// Returns the number of handles pointing to this lock's data, including this one.
+    pub fn ref_count(&self) -> usize {
+        Arc::strong_count(&self.lock)
+    }
+
+    /// Returns `true` if any other handle points to this lock's data.
+    /// ```
+    /// # use slock::*;
+    /// let lock = Slock::new(0i32);
+    /// assert!(!lock.has_other_refs());
+    ///
+    /// let other = lock.clone();
+    /// assert!(lock.has_other_refs());
+    /// ```
+    pub fn has_other_refs(&self) -> bool {
+        self.ref_count() > 1
+    }
+
+    /// The name given to this lock with [`SlockBuilder::named`], if any.
+    pub fn name(&self) -> Option<&str> {
+        self.lock.name.as_deref()
+    }
+
+    /// A stable identity for the data behind this lock, used to order acquisitions of several locks.
+    pub(crate) fn id(&self) -> usize {
+        Arc::as_ptr(&self.lock) as usize
+    }
+
+    /// An identity for the data behind this lock that is never shared with another lock, even after this one is dropped.
+    pub(crate) fn serial(&self) -> u64 {
+        self.lock.serial
+    }
+
+    /// A handle that doesn't keep the lock alive, for tasks that should stop once every other handle is dropped.
+    #[cfg(feature = "rt")]
+    pub(crate) fn downgrade(&self) -> std::sync::Weak<SlockInner<T>> {
+        Arc::downgrade(&self.lock)
+    }
+
+    /// Get a handle to the lock, unless every other handle has been dropped.
+    #[cfg(feature = "rt")]
+    pub(crate) fn upgrade(weak: &std::sync::Weak<SlockInner<T>>) -> Option<Self> {
+        weak.upgrade().map(|lock| Self { lock })
+    }
+
+    /// Record that this lock was just looked up.
+    #[cfg(feature = "time")]
+    pub(crate) fn touch(&self) {
+        *self.lock.accessed.lock().unwrap() = time::Instant::now();
+    }
+
+    /// How long it has been since this lock was last looked up.
+    #[cfg(feature = "time")]
+    pub(crate) fn idle_for(&self) -> std::time::Duration {
+        self.lock.accessed.lock().unwrap().elapsed()
+    }
+
     /// Subscribe to changes in the lock.
     ///
     /// `hook` will be called any time `Slock::set` is called.
-    pub async fn hook<F: 'static>(&self, hook: F)
+    /// It must be `Send + Sync`, since it is kept in the lock and runs on whichever task sets it,
+    /// which is what lets futures that set the lock be spawned.
+    pub async fn hook<F>(&self, hook: F)
+    where
+        F: FnMut(&T) + Send + Sync + 'static,
+    {
+        let mut data = self.write().await;
+        data.set_hook(Box::new(hook));
+    }
+
+    /// Like [`Slock::hook`], but only called when `projection` of the new value differs from the last one.
+    ///
+    /// `hook` receives the new projection.
+    /// ```rust
+    /// # use slock::*;
+    /// # struct Player { name: &'static str, x: f32 }
+    /// # async {
+    /// let player = Slock::new(Player { name: "bob", x: 0.0 });
+    /// player.hook_on(|p| p.name, |name| println!("renamed to {name}")).await;
+    ///
+    /// // Doesn't call the hook
+    /// player.set(|p| Player { x: p.x + 1.0, ..p }).await;
+    /// # };
+    /// ```
+    pub async fn hook_on<P, U, F>(&self, projection: P, mut hook: F)
+    where
+        P: Fn(&T) -> U + Send + Sync + 'static,
+        U: PartialEq + Send + Sync + 'static,
+        F: FnMut(&U) + Send + Sync + 'static,
+    {
+        let mut data = self.write().await;
+        let mut last = projection(&data.value);
+        data.set_hook(Box::new(move |value| {
+            let projected = projection(value);
+            if projected != last {
+                hook(&projected);
+                last = projected;
+            }
+        }));
+    }
+}
+
+impl<T> Slock<T> {
+    /// Create a new lock with data copied from this one by `copy`, for types that can be duplicated but aren't `Clone`.
+    ///
+    /// Gives up with [`Error::Timeout`] if the lock can't be read and copied within `limit`.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::time::Duration;
+    /// struct Connection { url: String }
+    ///
+    /// # async {
+    /// let lock = Slock::new(Connection { url: "db://primary".into() });
+    /// let copy = lock
+    ///     .try_clone_deep_with(Duration::from_millis(100), |c| Connection { url: c.url.clone() })
+    ///     .await
+    ///     .unwrap();
+    /// # };
+    /// ```
+    pub async fn try_clone_deep_with<F>(&self, limit: Duration, copy: F) -> Result<Self, Error>
     where
-        F: FnMut(&T),
+        F: FnOnce(&T) -> T,
     {
-        let mut data = self.lock.write().await;
-        data.hook = Some(Box::new(hook));
+        let copied = timeout(limit, async {
+            let data = self.read().await;
+            copy(&data.value)
+        })
+        .await;
+        if copied.is_err() {
+            self.lock.counters.timed_out();
+        }
+        Ok(Slock::new(copied?))
     }
 }
 
@@ -185,19 +903,110 @@ impl<T> Clone for Slock<T> {
 impl<T: Clone> Slock<T> {
     /// Returns a clone of the lock's data.
     pub async fn get_clone(&self) -> T {
-        let data = self.lock.read().await;
+        let data = self.read().await;
         data.value.clone()
     }
 
+    /// Returns a clone of the lock's data, or [`Error::Stalled`] if it can't be read within `limit`.
+    ///
+    /// The error says which lock stalled and how long it has been write locked for.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::time::Duration;
+    /// # async {
+    /// let config = Slock::builder().named("config").build(1u32);
+    /// match config.get_clone_timeout(Duration::from_secs(1)).await {
+    ///     Ok(config) => println!("{config}"),
+    ///     Err(e) => eprintln!("{e}"),
+    /// }
+    /// # };
+    /// ```
+    pub async fn get_clone_timeout(&self, limit: Duration) -> Result<T, Error> {
+        match timeout(limit, self.try_read()).await {
+            Ok(data) => Ok(data?.value.clone()),
+            Err(_) => {
+                self.lock.counters.timed_out();
+                Err(Error::Stalled {
+                    name: self.lock.name.clone(),
+                    held_for: self.lock.counters.held_for(),
+                    writer: self.lock.counters.writer(),
+                })
+            }
+        }
+    }
+
+    /// Returns a clone of the lock's data, or the result of `recover` if it can't be read within the lock's timeout.
+    ///
+    /// Unlike [`Slock::get_or_else`], `recover` is told why, as with [`Slock::get_clone_timeout`].
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let rates = Slock::new(vec![1.0f64]);
+    /// let shown = rates.read_or_recover(|e| {
+    ///     eprintln!("using no rates: {e}");
+    ///     Vec::new()
+    /// }).await;
+    /// # };
+    /// ```
+    pub async fn read_or_recover<F>(&self, recover: F) -> T
+    where
+        F: FnOnce(Error) -> T,
+    {
+        self.get_clone_timeout(self.lock.timeout)
+            .await
+            .unwrap_or_else(recover)
+    }
+
+    /// Returns a clone of the lock's data, or `default` if it can't be read within the lock's timeout.
+    pub async fn get_or(&self, default: T) -> T {
+        self.get_or_else(|| default).await
+    }
+
+    /// Returns a clone of the lock's data, or the result of `fallback` if it can't be read within the lock's timeout.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let price = Slock::new(10u32);
+    /// let shown = price.get_or_else(|| 0).await;
+    /// # };
+    /// ```
+    pub async fn get_or_else<F>(&self, fallback: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        match timeout(self.lock.timeout, self.read()).await {
+            Ok(data) => data.value.clone(),
+            Err(_) => {
+                self.lock.counters.timed_out();
+                fallback()
+            }
+        }
+    }
+
     /// Create a new lock with data clone from this one.
+    ///
+    /// For large values that are mostly read, consider a `Slock<Arc<T>>` and [`Slock::clone_cow`] instead.
     pub async fn clone_deep(&self) -> Self {
         return Slock::new(self.get_clone().await);
     }
+
+    /// Like [`Slock::clone_deep`], but gives up with [`Error::Timeout`] if the lock can't be read and cloned within `limit`.
+    pub async fn try_clone_deep(&self, limit: Duration) -> Result<Self, Error> {
+        self.try_clone_deep_with(limit, T::clone).await
+    }
+}
+
+impl<T: Clone + Default> Slock<T> {
+    /// Returns a clone of the lock's data, or `T::default()` if it can't be read within the lock's timeout.
+    pub async fn get_or_default(&self) -> T {
+        self.get_or_else(T::default).await
+    }
 }
 
 impl<T> Slock<Vec<T>> {
     /// Asyncronously push to a vec.
     /// Note that due to the nature of async code, order cannot be guaranteed.
+    /// Use [`Slock::ordered`] when it needs to be.
     pub async fn push(&self, value: T) {
         self.set(|mut v| {
             v.push(value);
@@ -210,53 +1019,21 @@ impl<T> Slock<Vec<T>> {
 impl<T> Slock<Slock<T>> {
     /// Converts from `Slock<Slock<T>>` to `Slock<T>`
     pub async fn flatten(&self) -> Slock<T> {
-        self.map(|inner| inner.clone()).await.unwrap()
-    }
-}
-
-/// ## HashMaps
-///
-/// Slock has built-in convenience methods for working with `Slock<HashMap<Slock>>`s
-pub type SlockMap<K, V> = Slock<HashMap<K, Slock<V>>>;
-
-impl<K: Eq + Hash + Copy, V> SlockMap<K, V> {
-    /// Create a new `Slock` powered `HashMap`
-    pub fn new_map() -> Slock<HashMap<K, Slock<V>>> {
-        let map: HashMap<K, Slock<V>> = HashMap::new();
-        Slock::new(map)
-    }
-
-    /// Insert / modify a value in the map at a given key.
-    pub async fn insert<F>(&self, key: K, setter: F)
-    where
-        F: FnOnce(Option<V>) -> V,
-    {
-        if let Some(data) = self.from_key(key).await {
-            data.set(|v| setter(Some(v))).await;
-        } else {
-            self.set(|mut hash_map| {
-                hash_map.insert(key, Slock::new(setter(None)));
-                hash_map
-            })
-            .await;
-        }
-    }
-
-    /// Get a value from the map at a given key.
-    pub async fn from_key(&self, key: K) -> Option<Slock<V>> {
-        self.map(|hash_map| {
-            let key = key;
-            hash_map.get(&key).map(|inner| inner.clone())
-        })
-        .await
-        .unwrap()
+        self.peek(|inner| inner.clone()).await
     }
 }
 
 impl<T: Copy> Slock<T> {
     /// If a lock's data implements copy, this will return an owned copy of it.
+    ///
+    /// Zero-sized types like `()` only have one value, so it is returned without acquiring the lock,
+    /// and isn't counted in [`Slock::metrics`].
     pub async fn get(&self) -> T {
-        let data = self.lock.read().await;
+        if std::mem::size_of::<T>() == 0 && !self.is_poisoned() {
+            // A zero-sized value has no bytes to read, and this lock proves one exists to be copied
+            return unsafe { std::ptr::read(std::ptr::NonNull::<T>::dangling().as_ptr()) };
+        }
+        let data = self.read().await;
         data.value
     }
 }
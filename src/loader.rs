@@ -0,0 +1,166 @@
+use std::{collections::HashMap, future::Future, hash::Hash, sync::Arc};
+
+use futures::future::BoxFuture;
+#[cfg(feature = "rt")]
+use tokio::sync::mpsc;
+use tokio::sync::OnceCell;
+
+use crate::{Error, Slock, SlockMap};
+
+type Load<K, V> = Arc<dyn Fn(K) -> BoxFuture<'static, V> + Send + Sync>;
+type Pending<K, V> = Slock<HashMap<K, Arc<OnceCell<Slock<V>>>>>;
+/// Queues changed values for the write-back task, along with how to copy them.
+#[cfg(feature = "rt")]
+type WriteBack<K, V> = (mpsc::UnboundedSender<(K, V)>, fn(&V) -> V);
+
+/// A read-through cache built on a [`SlockMap`].
+///
+/// Values are loaded on first use, and concurrent requests for the same key share a single load.
+/// ```rust
+/// # use slock::*;
+/// # async fn fetch_user(id: u64) -> String { String::new() }
+/// # async {
+/// let users = SlockLoader::new(|id: u64| fetch_user(id));
+///
+/// // Only one fetch happens, no matter how many tasks ask at once
+/// let (a, b) = tokio::join!(users.get_or_load(7), users.get_or_load(7));
+/// assert_eq!(a?.get_clone().await, b?.get_clone().await);
+/// # Ok::<(), Error>(())
+/// # };
+/// ```
+pub struct SlockLoader<K, V> {
+    cache: SlockMap<K, V>,
+    pending: Pending<K, V>,
+    load: Load<K, V>,
+    #[cfg(feature = "rt")]
+    write_back: Option<WriteBack<K, V>>,
+}
+
+impl<K, V> SlockLoader<K, V>
+where
+    K: Eq + Hash + Copy + Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    /// Create an empty cache which loads missing values with `loader`.
+    pub fn new<F, Fut>(loader: F) -> Self
+    where
+        F: Fn(K) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = V> + Send + 'static,
+    {
+        Self {
+            cache: SlockMap::new_map(),
+            pending: Slock::new(HashMap::new()),
+            load: Arc::new(move |key| Box::pin(loader(key))),
+            #[cfg(feature = "rt")]
+            write_back: None,
+        }
+    }
+
+    /// Call `write_back` with the new value whenever a loaded value is set.
+    ///
+    /// Writes are performed in order by a background task.
+    /// Write-back is installed on each lock loaded afterwards, alongside any [`Slock::hook`] set on it.
+    #[cfg(feature = "rt")]
+    pub fn with_write_back<F, Fut>(mut self, write_back: F) -> Self
+    where
+        V: Clone,
+        F: Fn(K, V) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some((key, value)) = receiver.recv().await {
+                write_back(key, value).await;
+            }
+        });
+        self.write_back = Some((sender, V::clone));
+        self
+    }
+
+    /// The values loaded so far.
+    pub fn cache(&self) -> &SlockMap<K, V> {
+        &self.cache
+    }
+
+    /// Get the value at `key`, loading it if it isn't cached yet.
+    ///
+    /// Returns any error from [`Slock::try_set`] on the cache.
+    pub async fn get_or_load(&self, key: K) -> Result<Slock<V>, Error> {
+        if let Some(value) = self.cache.from_key(key).await {
+            return Ok(value);
+        }
+
+        let mut cell = None;
+        self.pending
+            .try_set(|mut pending| {
+                cell = Some(pending.entry(key).or_default().clone());
+                pending
+            })
+            .await?;
+        let cell = cell.expect("the setter ran, since the set succeeded");
+
+        let value = cell
+            .get_or_init(|| async {
+                let value = Slock::new((self.load)(key).await);
+                self.install_write_back(key, &value).await;
+                value
+            })
+            .await
+            .clone();
+
+        let mut cached = None;
+        let stored = self
+            .cache
+            .try_set(|mut cache| {
+                cached = Some(cache.entry(key).or_insert(value).clone());
+                cache
+            })
+            .await;
+        // Even if caching failed, the next request should start a fresh load
+        self.pending
+            .set(|mut pending| {
+                if pending.get(&key).is_some_and(|p| Arc::ptr_eq(p, &cell)) {
+                    pending.remove(&key);
+                }
+                pending
+            })
+            .await;
+        stored?;
+        Ok(cached.expect("the setter ran, since the set succeeded"))
+    }
+
+    /// Drop the cached value at `key`, so the next request loads it again.
+    pub async fn invalidate(&self, key: K) {
+        self.cache
+            .set(|mut cache| {
+                cache.remove(&key);
+                cache
+            })
+            .await;
+    }
+
+    #[cfg(feature = "rt")]
+    async fn install_write_back(&self, key: K, value: &Slock<V>) {
+        let Some((sender, snapshot)) = self.write_back.clone() else {
+            return;
+        };
+        value.add_hook(Box::new(move |v| {
+            sender.send((key, snapshot(v))).ok();
+        }));
+    }
+
+    #[cfg(not(feature = "rt"))]
+    async fn install_write_back(&self, _key: K, _value: &Slock<V>) {}
+}
+
+impl<K, V> Clone for SlockLoader<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            cache: self.cache.clone(),
+            pending: self.pending.clone(),
+            load: self.load.clone(),
+            #[cfg(feature = "rt")]
+            write_back: self.write_back.clone(),
+        }
+    }
+}
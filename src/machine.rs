@@ -0,0 +1,103 @@
+use crate::{
+    event::{self, SlockEvent},
+    Error, Slock,
+};
+
+type Listener<S> = Box<dyn FnMut(&S, &S) + Send + Sync>;
+
+/// Shared state that moves between a fixed set of states, such as a connection status or job lifecycle.
+///
+/// Transitions only happen from the states they allow, and every transition is reported to listeners.
+/// ```rust
+/// # use slock::*;
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Job { Queued, Running, Done }
+///
+/// # async {
+/// let job = SlockMachine::new(Job::Queued);
+/// job.on_transition(|from, to| println!("{from:?} -> {to:?}")).await;
+///
+/// job.transition(|s| matches!(s, Job::Queued), Job::Running).await.unwrap();
+/// assert!(job.transition(|s| matches!(s, Job::Queued), Job::Running).await.is_err());
+/// # };
+/// ```
+pub struct SlockMachine<S> {
+    state: Slock<S>,
+    listeners: Slock<Vec<Listener<S>>>,
+}
+
+impl<S: Clone> SlockMachine<S> {
+    /// Create a machine in its initial state.
+    pub fn new(initial: S) -> Self {
+        Self {
+            state: Slock::new(initial),
+            listeners: Slock::new(Vec::new()),
+        }
+    }
+
+    /// The lock holding the current state, for waiting on or hooking.
+    pub fn lock(&self) -> &Slock<S> {
+        &self.state
+    }
+
+    /// Returns a clone of the current state.
+    pub async fn state(&self) -> S {
+        self.state.get_clone().await
+    }
+
+    /// Move to `to`, but only if the current state matches `from`.
+    ///
+    /// Returns the previous state, or [`Error::InvalidTransition`] without changing anything.
+    pub async fn transition<F>(&self, from: F, to: S) -> Result<S, Error>
+    where
+        F: FnOnce(&S) -> bool,
+    {
+        let next = to.clone();
+        let (previous, moved) = self
+            .state
+            .update(|current| {
+                if from(current) {
+                    (Some(next), Some(current.clone()))
+                } else {
+                    (None, None)
+                }
+            })
+            .await?;
+        let previous = match previous {
+            Some(previous) if moved => previous,
+            _ => return Err(Error::InvalidTransition),
+        };
+        let mut listeners = self.listeners.write().await;
+        for listener in listeners.value.iter_mut() {
+            let called = self.state.contain(async { listener(&previous, &to) });
+            if let Err(error) = called.await {
+                event::emit(SlockEvent::HookFailed {
+                    lock: self.state.name(),
+                    label: None,
+                    error: &error,
+                });
+            }
+        }
+        Ok(previous)
+    }
+
+    /// Call `listener` with the previous and new state after every transition.
+    ///
+    /// A listener that panics or times out is reported to the [`set_event_logger`](crate::set_event_logger) logger like a failed hook,
+    /// and keeps being called for later transitions.
+    pub async fn on_transition<F>(&self, listener: F)
+    where
+        F: FnMut(&S, &S) + Send + Sync + 'static,
+    {
+        self.listeners.push(Box::new(listener)).await;
+    }
+}
+
+impl<S> Clone for SlockMachine<S> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            listeners: self.listeners.clone(),
+        }
+    }
+}
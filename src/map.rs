@@ -0,0 +1,502 @@
+use std::{
+    cmp::Eq,
+    collections::{hash_map::RandomState, BTreeMap, HashMap},
+    future::Future,
+    hash::{BuildHasher, Hash},
+};
+
+use futures::{stream, Stream};
+
+#[cfg(feature = "time")]
+use std::time::Duration;
+#[cfg(all(feature = "rt", feature = "time"))]
+use tokio::task::JoinHandle;
+
+use crate::{Error, Slock};
+
+/// ## HashMaps
+///
+/// Slock has built-in convenience methods for working with `Slock<HashMap<Slock>>`s
+///
+/// Like `HashMap`, a custom hasher can be used in place of the default `RandomState`.
+pub type SlockMap<K, V, S = RandomState> = Slock<HashMap<K, Slock<V>, S>>;
+
+impl<K: Eq + Hash + Copy, V> SlockMap<K, V> {
+    /// Create a new `Slock` powered `HashMap`
+    pub fn new_map() -> Slock<HashMap<K, Slock<V>>> {
+        let map: HashMap<K, Slock<V>> = HashMap::new();
+        Slock::new(map)
+    }
+}
+
+impl<K: Eq + Hash + Copy + 'static, V: 'static, S: BuildHasher> SlockMap<K, V, S> {
+    /// Create a new `Slock` powered `HashMap` which will use the given hash builder to hash keys.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::{collections::hash_map::DefaultHasher, hash::BuildHasherDefault};
+    /// type FastHasher = BuildHasherDefault<DefaultHasher>;
+    ///
+    /// let routes: SlockMap<u32, String, FastHasher> = SlockMap::with_hasher(FastHasher::default());
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Slock::new(HashMap::with_hasher(hash_builder))
+    }
+
+    /// Insert / modify a value in the map at a given key.
+    pub async fn insert<F>(&self, key: K, setter: F)
+    where
+        F: FnOnce(Option<V>) -> V,
+    {
+        if let Some(data) = self.from_key(key).await {
+            data.set(|v| setter(Some(v))).await;
+        } else {
+            let inner = Slock::new(setter(None));
+            self.set(|mut hash_map| {
+                hash_map.insert(key, inner.clone());
+                hash_map
+            })
+            .await;
+            self.inserted(&[(key, inner)]).await;
+        }
+    }
+
+    /// Remove the value at a given key, returning its lock.
+    pub async fn remove(&self, key: K) -> Option<Slock<V>> {
+        let mut removed = None;
+        self.set(|mut hash_map| {
+            removed = hash_map.remove(&key);
+            hash_map
+        })
+        .await;
+        if removed.is_some() {
+            self.removed(&[key]);
+        }
+        removed
+    }
+
+    /// Insert / replace many values under a single write acquisition of the map.
+    ///
+    /// Keys already in the map keep their locks, which are set to the new values once the map is unlocked.
+    /// The map's version only changes once, and [`SlockMap::on_insert`] subscribers hear about the new keys after it is unlocked.
+    /// Returns the number of keys added.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let users = SlockMap::new_map();
+    /// let added = users.insert_many((0..1000u32).map(|id| (id, format!("user {id}")))).await;
+    /// assert_eq!(added, 1000);
+    /// # };
+    /// ```
+    pub async fn insert_many<I>(&self, entries: I) -> usize
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        // Waiting for room in the write queue never fails
+        let _queued = self.enqueue(false).await;
+        let mut data = self.write_as(None).await;
+        let mut added = Vec::new();
+        let mut updated = Vec::new();
+        for (key, value) in entries {
+            match data.value.get(&key) {
+                Some(inner) => updated.push((inner.clone(), value)),
+                None => {
+                    let inner = Slock::new(value);
+                    data.value.insert(key, inner.clone());
+                    added.push((key, inner));
+                }
+            }
+        }
+        if !added.is_empty() {
+            self.publish(&mut data).await;
+        }
+        drop(data);
+        // Setting the kept locks waits on them, so it happens after the map is unlocked
+        for (inner, value) in updated {
+            inner.set(|_| value).await;
+        }
+        self.inserted(&added).await;
+        added.len()
+    }
+
+    /// Replace the map's entries with `entries`, such as a saved [`SlockMap::export`], in a single write acquisition of the map.
+    ///
+    /// Keys kept from the old map keep their locks, which are set to the imported values once the map is unlocked.
+    /// [`SlockMap::on_insert`] and [`SlockMap::on_remove`] subscribers hear about added and removed keys.
+    pub async fn import<S2>(&self, mut entries: HashMap<K, V, S2>)
+    where
+        S2: BuildHasher,
+    {
+        // Waiting for room in the write queue never fails
+        let _queued = self.enqueue(false).await;
+        let mut data = self.write_as(None).await;
+        let mut removed = Vec::new();
+        let mut updated = Vec::new();
+        for (key, inner) in data.value.iter() {
+            match entries.remove(key) {
+                Some(value) => updated.push((inner.clone(), value)),
+                None => removed.push(*key),
+            }
+        }
+        for key in removed.iter() {
+            data.value.remove(key);
+        }
+        let mut added = Vec::new();
+        for (key, value) in entries {
+            let inner = Slock::new(value);
+            data.value.insert(key, inner.clone());
+            added.push((key, inner));
+        }
+        if !removed.is_empty() || !added.is_empty() {
+            self.publish(&mut data).await;
+        }
+        drop(data);
+        for (inner, value) in updated {
+            inner.set(|_| value).await;
+        }
+        self.removed(&removed);
+        self.inserted(&added).await;
+    }
+
+    /// Get a value from the map at a given key.
+    pub async fn from_key(&self, key: K) -> Option<Slock<V>> {
+        self.peek(|hash_map| lookup(hash_map, &key)).await
+    }
+
+    /// Get the values at several keys under a single acquisition of the map.
+    ///
+    /// Results are returned in the same order as `keys`.
+    pub async fn get_many(&self, keys: &[K]) -> Vec<Option<Slock<V>>> {
+        self.peek(|hash_map| keys.iter().map(|key| lookup(hash_map, key)).collect())
+            .await
+    }
+
+    /// Remove every entry whose value is no longer referenced outside of the map.
+    ///
+    /// Returns the number of entries removed.
+    pub async fn prune_unreferenced(&self) -> usize {
+        self.prune(|_| true).await
+    }
+
+    /// Removes unreferenced entries matching `condition` under a single write acquisition,
+    /// so no new references can be handed out part way through.
+    async fn prune<F>(&self, mut condition: F) -> usize
+    where
+        F: FnMut(&Slock<V>) -> bool,
+    {
+        let mut removed = Vec::new();
+        self.set(|mut hash_map| {
+            hash_map.retain(|key, inner| {
+                let keep = inner.has_other_refs() || !condition(inner);
+                if !keep {
+                    removed.push(*key);
+                }
+                keep
+            });
+            hash_map
+        })
+        .await;
+        self.removed(&removed);
+        removed.len()
+    }
+
+    /// Returns every key paired with its value's lock.
+    ///
+    /// The map is only locked while the entries are collected, not while they are used.
+    pub async fn entries(&self) -> Vec<(K, Slock<V>)> {
+        self.peek(|hash_map| {
+            hash_map
+                .iter()
+                .map(|(key, inner)| (*key, inner.clone()))
+                .collect()
+        })
+        .await
+    }
+
+    /// Visit every entry of the map, one at a time.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let scores = SlockMap::new_map();
+    /// scores.insert("bob", |_| 10i32).await;
+    ///
+    /// scores.for_each(|_name, score| async move {
+    ///     score.set(|v| v + 1).await;
+    /// }).await;
+    /// # };
+    /// ```
+    pub async fn for_each<F, Fut>(&self, mut visitor: F)
+    where
+        F: FnMut(K, Slock<V>) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        for (key, inner) in self.entries().await {
+            visitor(key, inner).await;
+        }
+    }
+
+    /// A stream over every entry of the map, taken from a snapshot of its keys.
+    pub async fn iter(&self) -> impl Stream<Item = (K, Slock<V>)> {
+        stream::iter(self.entries().await)
+    }
+}
+
+#[cfg(feature = "time")]
+impl<K: Eq + Hash + Copy + 'static, V: 'static, S: BuildHasher> SlockMap<K, V, S> {
+    /// Remove every entry that is unreferenced outside of the map and hasn't been looked up within `ttl`.
+    ///
+    /// Returns the number of entries removed.
+    pub async fn prune_idle(&self, ttl: Duration) -> usize {
+        self.prune(|inner| inner.idle_for() >= ttl).await
+    }
+
+    /// Spawn a task that runs [`SlockMap::prune_idle`] every `period`.
+    ///
+    /// The task stops on its own once every handle to the map has been dropped.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::time::Duration;
+    /// # async {
+    /// let sessions: SlockMap<u64, String> = SlockMap::new_map();
+    /// sessions.spawn_pruner(Duration::from_secs(600), Duration::from_secs(60));
+    /// # };
+    /// ```
+    #[cfg(feature = "rt")]
+    pub fn spawn_pruner(&self, ttl: Duration, period: Duration) -> JoinHandle<()>
+    where
+        K: Send + Sync + 'static,
+        V: Send + Sync + 'static,
+        S: Send + Sync + 'static,
+    {
+        let weak = self.downgrade();
+        tokio::spawn(async move {
+            let mut interval = crate::time::interval(period);
+            loop {
+                interval.tick().await;
+                let Some(hash_map) = Slock::upgrade(&weak) else {
+                    break;
+                };
+                hash_map.prune_idle(ttl).await;
+            }
+        })
+    }
+}
+
+impl<K: Eq + Hash + Copy + 'static, V: Default + 'static, S: BuildHasher> SlockMap<K, V, S> {
+    /// Get the value at a given key, inserting a default value if there isn't one.
+    ///
+    /// Creation happens under a single write acquisition, so concurrent callers always share the same lock.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let visits: SlockMap<&str, i32> = SlockMap::new_map();
+    /// visits.at("home").await.set(|v| v + 1).await;
+    /// # };
+    /// ```
+    pub async fn at(&self, key: K) -> Slock<V> {
+        if let Some(inner) = self.from_key(key).await {
+            return inner;
+        }
+        let mut entry = None;
+        let mut created = false;
+        self.set(|mut hash_map| {
+            entry = Some(
+                hash_map
+                    .entry(key)
+                    .or_insert_with(|| {
+                        created = true;
+                        Slock::new(V::default())
+                    })
+                    .clone(),
+            );
+            hash_map
+        })
+        .await;
+        let entry = entry.expect("setter should always run");
+        if created {
+            self.inserted(&[(key, entry.clone())]).await;
+        }
+        entry
+    }
+}
+
+/// ## Nested HashMaps
+///
+/// State indexed by two keys, such as guild and user, can be held in a `SlockMap` of `SlockMap`s.
+/// ```rust
+/// # use slock::*;
+/// # use std::collections::HashMap;
+/// # async {
+/// let scores: SlockMap<u64, HashMap<u64, Slock<i32>>> = SlockMap::new_map();
+/// scores.at(1).await.at(42).await.set(|v| v + 10).await;
+/// scores.insert_path(1, 7, |v| v.unwrap_or(0) + 5).await;
+/// # };
+/// ```
+impl<K1, K2, V, S1, S2> SlockMap<K1, HashMap<K2, Slock<V>, S2>, S1>
+where
+    K1: Eq + Hash + Copy + 'static,
+    K2: Eq + Hash + Copy + 'static,
+    V: 'static,
+    S1: BuildHasher,
+    S2: BuildHasher + Default + 'static,
+{
+    /// Get a value from the inner map at `key_2`, inside the outer map at `key_1`.
+    pub async fn get_path(&self, key_1: K1, key_2: K2) -> Option<Slock<V>> {
+        self.from_key(key_1).await?.from_key(key_2).await
+    }
+
+    /// Insert / modify a value in the inner map at `key_2`, creating the inner map at `key_1` if needed.
+    pub async fn insert_path<F>(&self, key_1: K1, key_2: K2, setter: F)
+    where
+        F: FnOnce(Option<V>) -> V,
+    {
+        self.at(key_1).await.insert(key_2, setter).await;
+    }
+}
+
+impl<K: Eq + Hash + Copy, V: Clone, S: BuildHasher + Clone> SlockMap<K, V, S> {
+    /// Clone every value in the map.
+    ///
+    /// The map stays locked for the whole snapshot, so no entries can be added or removed part way through.
+    /// Fails with [`Error::Poisoned`] if the map or any of its values is poisoned.
+    pub async fn snapshot(&self) -> Result<HashMap<K, V, S>, Error> {
+        let hash_map = self.try_read().await?;
+        let mut snapshot = HashMap::with_capacity_and_hasher(
+            hash_map.value.len(),
+            hash_map.value.hasher().clone(),
+        );
+        for (key, inner) in hash_map.value.iter() {
+            snapshot.insert(*key, inner.try_read().await?.value.clone());
+        }
+        Ok(snapshot)
+    }
+
+    /// Clone every value in the map, all read at the same moment, for saving the whole map.
+    ///
+    /// Unlike [`SlockMap::snapshot`], every value is held for reading until all have been cloned,
+    /// so the export never mixes values from before and after a related update. Restore it with [`SlockMap::import`].
+    /// Fails with [`Error::Poisoned`] if the map or any of its values is poisoned.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let settings = SlockMap::new_map();
+    /// settings.insert("theme", |_| "dark").await;
+    ///
+    /// let saved = settings.export().await.unwrap();
+    /// let restored = SlockMap::new_map();
+    /// restored.import(saved).await;
+    /// assert_eq!(restored.from_key("theme").await.unwrap().get().await, "dark");
+    /// # };
+    /// ```
+    pub async fn export(&self) -> Result<HashMap<K, V, S>, Error> {
+        let hash_map = self.try_read().await?;
+        // A lock may be stored under several keys, but is only read once
+        let mut locks = BTreeMap::new();
+        for inner in hash_map.value.values() {
+            locks.entry(inner.id()).or_insert(inner);
+        }
+        let mut values = BTreeMap::new();
+        for (id, inner) in locks {
+            values.insert(id, inner.try_read().await?);
+        }
+
+        let mut export = HashMap::with_capacity_and_hasher(
+            hash_map.value.len(),
+            hash_map.value.hasher().clone(),
+        );
+        for (key, inner) in hash_map.value.iter() {
+            export.insert(*key, values[&inner.id()].value.clone());
+        }
+        Ok(export)
+    }
+}
+
+impl<K: Eq + Hash + Copy, V: Clone, S: BuildHasher> SlockMap<K, V, S> {
+    /// Returns a clone of the entry with the greatest key computed by `key_fn`.
+    ///
+    /// The whole query runs under a single acquisition of the map.
+    /// Like [`Slock::map`], `key_fn` runs under each value's timeout, and fails the query if it panics.
+    /// A poisoned map or value fails the query with [`Error::Poisoned`].
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let scores = SlockMap::new_map();
+    /// scores.insert("bob", |_| 10i32).await;
+    /// scores.insert("alice", |_| 30i32).await;
+    ///
+    /// assert_eq!(scores.max_by_key(|v| *v).await.unwrap(), Some(("alice", 30)));
+    /// # };
+    /// ```
+    pub async fn max_by_key<B, F>(&self, key_fn: F) -> Result<Option<(K, V)>, Error>
+    where
+        B: Ord,
+        F: FnMut(&V) -> B,
+    {
+        self.best_by_key(key_fn, |candidate, best| candidate > best)
+            .await
+    }
+
+    /// Returns a clone of the entry with the least key computed by `key_fn`.
+    ///
+    /// See [`SlockMap::max_by_key`].
+    pub async fn min_by_key<B, F>(&self, key_fn: F) -> Result<Option<(K, V)>, Error>
+    where
+        B: Ord,
+        F: FnMut(&V) -> B,
+    {
+        self.best_by_key(key_fn, |candidate, best| candidate < best)
+            .await
+    }
+
+    /// Returns a clone of any entry matching `predicate`.
+    ///
+    /// See [`SlockMap::max_by_key`].
+    pub async fn find<F>(&self, mut predicate: F) -> Result<Option<(K, V)>, Error>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        let hash_map = self.try_read().await?;
+        for (key, inner) in hash_map.value.iter() {
+            let value = inner.try_read().await?;
+            if inner
+                .contain(async { predicate(key, &value.value) })
+                .await?
+            {
+                return Ok(Some((*key, value.value.clone())));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Clones the entry whose key `beats` every other, only cloning values as they take the lead.
+    async fn best_by_key<B, F, C>(&self, mut key_fn: F, beats: C) -> Result<Option<(K, V)>, Error>
+    where
+        F: FnMut(&V) -> B,
+        C: Fn(&B, &B) -> bool,
+    {
+        let hash_map = self.try_read().await?;
+        let mut best: Option<(B, K, V)> = None;
+        for (key, inner) in hash_map.value.iter() {
+            let value = inner.try_read().await?;
+            let candidate = inner.contain(async { key_fn(&value.value) }).await?;
+            let leads = match &best {
+                Some((lead, _, _)) => beats(&candidate, lead),
+                None => true,
+            };
+            if leads {
+                best = Some((candidate, *key, value.value.clone()));
+            }
+        }
+        Ok(best.map(|(_, key, value)| (key, value)))
+    }
+}
+
+/// Clone the lock at `key`, recording the lookup for idle pruning.
+fn lookup<K: Eq + Hash, V, S: BuildHasher>(
+    hash_map: &HashMap<K, Slock<V>, S>,
+    key: &K,
+) -> Option<Slock<V>> {
+    let inner = hash_map.get(key)?;
+    #[cfg(feature = "time")]
+    inner.touch();
+    Some(inner.clone())
+}
@@ -0,0 +1,209 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{Slock, SlockMap};
+
+/// A change to one key of a [`SlockMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MapChange<K> {
+    /// The key was added to the map.
+    Added(K),
+    /// The key was removed from the map.
+    Removed(K),
+    /// The key stayed in the map, but its value changed.
+    Changed(K),
+}
+
+/// A lock's map subscribers, stored without naming the map's key and value types.
+pub(crate) type AnyListeners = Mutex<Option<Box<dyn Any + Send>>>;
+
+type ChangeListener<K> = Box<dyn FnMut(MapChange<K>) + Send>;
+type InsertListener<K, V> = Box<dyn FnMut(K, &V) + Send>;
+type RemoveListener<K> = Box<dyn FnMut(K) + Send>;
+
+struct MapListeners<K, V> {
+    changes: Vec<ChangeListener<K>>,
+    inserts: Vec<InsertListener<K, V>>,
+    removes: Vec<RemoveListener<K>>,
+}
+
+impl<K: Copy + 'static, V: 'static, S> SlockMap<K, V, S> {
+    /// Runs `f` on this map's subscribers, creating the lists if needed.
+    ///
+    /// A panicking subscriber leaves the lists intact, so the poisoning it causes is ignored.
+    fn with_listeners<R>(&self, f: impl FnOnce(&mut MapListeners<K, V>) -> R) -> R {
+        let mut listeners = self
+            .lock
+            .map_listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let listeners = listeners
+            .get_or_insert_with(|| {
+                Box::new(MapListeners::<K, V> {
+                    changes: Vec::new(),
+                    inserts: Vec::new(),
+                    removes: Vec::new(),
+                })
+            })
+            .downcast_mut()
+            .expect("a lock's key and value types never change");
+        f(listeners)
+    }
+
+    /// Subscribe to new keys being added to the map, along with their first value.
+    ///
+    /// Unlike a hook on one of the values, this only hears about membership changes.
+    /// Subscribers are called after the map is unlocked, but must not subscribe again from inside the callback.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let sessions = SlockMap::new_map();
+    /// sessions.on_insert(|id, user| println!("{user} signed in as {id}"));
+    /// sessions.on_remove(|id| println!("session {id} ended"));
+    ///
+    /// sessions.insert(1u32, |_| "bob").await;
+    /// sessions.remove(1).await;
+    /// # };
+    /// ```
+    pub fn on_insert<F>(&self, listener: F)
+    where
+        F: FnMut(K, &V) + Send + 'static,
+    {
+        self.with_listeners(|listeners| listeners.inserts.push(Box::new(listener)));
+    }
+
+    /// Subscribe to keys being removed from the map.
+    ///
+    /// See [`SlockMap::on_insert`].
+    pub fn on_remove<F>(&self, listener: F)
+    where
+        F: FnMut(K) + Send + 'static,
+    {
+        self.with_listeners(|listeners| listeners.removes.push(Box::new(listener)));
+    }
+
+    /// Tell insert subscribers about new entries.
+    pub(crate) async fn inserted(&self, entries: &[(K, Slock<V>)]) {
+        if self.with_listeners(|listeners| listeners.inserts.is_empty()) {
+            return;
+        }
+        for (key, inner) in entries {
+            let value = inner.read().await;
+            self.with_listeners(|listeners| {
+                for listener in listeners.inserts.iter_mut() {
+                    listener(*key, &value.value);
+                }
+            });
+        }
+    }
+
+    /// Tell remove subscribers about removed keys.
+    pub(crate) fn removed(&self, keys: &[K]) {
+        self.with_listeners(|listeners| {
+            for listener in listeners.removes.iter_mut() {
+                keys.iter().for_each(|key| listener(*key));
+            }
+        });
+    }
+
+    /// Subscribe to the per-key changes made by [`SlockMap::replace_all`].
+    ///
+    /// Subscribers are called after the map is unlocked, but must not subscribe again from inside the callback.
+    pub fn on_change<F>(&self, listener: F)
+    where
+        F: FnMut(MapChange<K>) + Send + 'static,
+    {
+        self.with_listeners(|listeners| listeners.changes.push(Box::new(listener)));
+    }
+
+    fn emit(&self, changes: &[MapChange<K>]) {
+        if changes.is_empty() {
+            return;
+        }
+        self.with_listeners(|listeners| {
+            for listener in listeners.changes.iter_mut() {
+                changes.iter().for_each(|change| listener(*change));
+            }
+        });
+    }
+}
+
+impl<K, V, S> SlockMap<K, V, S>
+where
+    K: Eq + Hash + Copy + 'static,
+    V: PartialEq + 'static,
+    S: BuildHasher,
+{
+    /// Swap in an entirely new set of entries, in a single write acquisition of the map.
+    ///
+    /// Keys kept from the old map keep their locks, so existing handles see the new values, which are set once the map is unlocked.
+    /// Every added, removed and changed key is reported to [`SlockMap::on_change`] subscribers, and returned.
+    /// Added and removed keys are also reported to [`SlockMap::on_insert`] and [`SlockMap::on_remove`] subscribers.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::collections::HashMap;
+    /// # async {
+    /// let routes = SlockMap::new_map();
+    /// routes.insert("/", |_| "index").await;
+    /// routes.insert("/old", |_| "legacy").await;
+    /// routes.on_change(|change| println!("{change:?}"));
+    ///
+    /// let changes = routes.replace_all(HashMap::from([("/", "home"), ("/new", "fresh")])).await;
+    /// assert_eq!(changes.len(), 3);
+    /// # };
+    /// ```
+    pub async fn replace_all(&self, mut new_map: HashMap<K, V, S>) -> Vec<MapChange<K>> {
+        // Waiting for room in the write queue never fails
+        let _queued = self.enqueue(false).await;
+        let mut data = self.write_as(None).await;
+        let mut membership = Vec::new();
+
+        let mut removed = Vec::new();
+        let mut added = Vec::new();
+        let mut kept = Vec::new();
+        for (key, inner) in data.value.iter() {
+            match new_map.remove(key) {
+                Some(new) => kept.push((*key, inner.clone(), new)),
+                None => removed.push(*key),
+            }
+        }
+        for key in removed.iter() {
+            data.value.remove(key);
+            membership.push(MapChange::Removed(*key));
+        }
+        for (key, value) in new_map {
+            let inner = Slock::new(value);
+            data.value.insert(key, inner.clone());
+            added.push((key, inner));
+            membership.push(MapChange::Added(key));
+        }
+        if !membership.is_empty() {
+            self.publish(&mut data).await;
+        }
+        drop(data);
+
+        // Setting the kept locks waits on them, so it happens after the map is unlocked
+        let mut changes = Vec::new();
+        for (key, inner, new) in kept {
+            let update = inner.update(|old| {
+                if *old == new {
+                    (None, ())
+                } else {
+                    (Some(new), ())
+                }
+            });
+            if let Ok(((), true)) = update.await {
+                changes.push(MapChange::Changed(key));
+            }
+        }
+        changes.extend(membership);
+        self.emit(&changes);
+        self.removed(&removed);
+        self.inserted(&added).await;
+        changes
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ptr,
+    sync::{
+        atomic::{AtomicPtr, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::Slock;
+
+/// A snapshot of a lock's health.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// How many times the lock has been acquired, for reading or writing.
+    pub acquisitions: u64,
+    /// The total time spent waiting to acquire the lock.
+    ///
+    /// Always zero without the `time` feature.
+    pub wait_time: Duration,
+    /// How many lock operations have timed out.
+    pub timeouts: u64,
+    /// How many setters finished after their deadline, see [`Slock::set_with_deadline`].
+    ///
+    /// Always zero without the `time` feature.
+    pub overruns: u64,
+    /// How many reads outlasted the limit they were taken for, see [`Slock::read_for`].
+    ///
+    /// Always zero without the `time` feature.
+    pub lease_violations: u64,
+    /// How many times the lock has been set.
+    pub version: u64,
+}
+
+/// The share of a lock's [`Metrics`] from calls made with one label. See [`Slock::instrument`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CallsiteMetrics {
+    /// How many times the lock has been acquired with this label.
+    pub acquisitions: u64,
+    /// The total time spent waiting to acquire the lock with this label.
+    ///
+    /// Always zero without the `time` feature.
+    pub wait_time: Duration,
+    /// How many calls with this label have timed out.
+    pub timeouts: u64,
+}
+
+/// Every label a write lock has been held under, each kept once for the whole program,
+/// so a lock's writer can be shared as a single pointer.
+static LABELS: Mutex<BTreeMap<&'static str, &'static &'static str>> = Mutex::new(BTreeMap::new());
+
+/// Returns the one place `label` is kept, leaking it the first time it is seen by any lock.
+fn intern(label: &'static str) -> &'static &'static str {
+    let mut labels = LABELS.lock().unwrap_or_else(|e| e.into_inner());
+    labels
+        .entry(label)
+        .or_insert_with(|| Box::leak(Box::new(label)))
+}
+
+/// The counters behind [`Metrics`], updated without taking the lock.
+pub(crate) struct Counters {
+    enabled: bool,
+    acquisitions: AtomicU64,
+    wait_nanos: AtomicU64,
+    timeouts: AtomicU64,
+    overruns: AtomicU64,
+    lease_violations: AtomicU64,
+    /// When the lock was last write locked, as nanoseconds since `created` plus one, or zero while it isn't.
+    held_at: AtomicU64,
+    #[cfg(feature = "time")]
+    created: Instant,
+    callsites: Mutex<HashMap<&'static str, CallsiteMetrics>>,
+    /// The label of the call holding the write lock, or null if it has none.
+    writer: AtomicPtr<&'static str>,
+}
+
+impl Counters {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            acquisitions: AtomicU64::new(0),
+            wait_nanos: AtomicU64::new(0),
+            timeouts: AtomicU64::new(0),
+            overruns: AtomicU64::new(0),
+            lease_violations: AtomicU64::new(0),
+            held_at: AtomicU64::new(0),
+            #[cfg(feature = "time")]
+            created: Instant::now(),
+            callsites: Mutex::new(HashMap::new()),
+            writer: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Marks the start of an acquisition, if wait times are being measured.
+    pub(crate) fn start(&self) -> Option<Instant> {
+        if !self.enabled || cfg!(not(feature = "time")) {
+            return None;
+        }
+        Some(Instant::now())
+    }
+
+    pub(crate) fn acquired(&self, started: Option<Instant>) {
+        if !self.enabled {
+            return;
+        }
+        self.acquisitions.fetch_add(1, Ordering::Relaxed);
+        if let Some(started) = started {
+            let waited = started.elapsed().as_nanos() as u64;
+            self.wait_nanos.fetch_add(waited, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn timed_out(&self) {
+        if !self.enabled {
+            return;
+        }
+        self.timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Marks the lock as write locked from now.
+    pub(crate) fn write_locked(&self) {
+        #[cfg(feature = "time")]
+        if self.enabled {
+            let at = self.created.elapsed().as_nanos() as u64 + 1;
+            self.held_at.store(at, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn write_unlocked(&self) {
+        self.held_at.store(0, Ordering::Relaxed);
+        self.writer.store(ptr::null_mut(), Ordering::Relaxed);
+    }
+
+    /// Runs `f` on the metrics for `label`.
+    fn callsite(&self, label: &'static str, f: impl FnOnce(&mut CallsiteMetrics)) {
+        let mut callsites = self.callsites.lock().unwrap_or_else(|e| e.into_inner());
+        f(callsites.entry(label).or_default());
+    }
+
+    /// Like `acquired`, for a call with `label`.
+    pub(crate) fn acquired_by(&self, label: &'static str, started: Option<Instant>) {
+        if !self.enabled {
+            return;
+        }
+        self.callsite(label, |metrics| {
+            metrics.acquisitions += 1;
+            if let Some(started) = started {
+                metrics.wait_time += started.elapsed();
+            }
+        });
+    }
+
+    pub(crate) fn timed_out_by(&self, label: &'static str) {
+        if !self.enabled {
+            return;
+        }
+        self.callsite(label, |metrics| metrics.timeouts += 1);
+    }
+
+    /// Marks the write lock as held by a call with `label`, until it is unlocked.
+    pub(crate) fn write_locked_by(&self, label: &'static str) {
+        let label = intern(label);
+        self.writer
+            .store(label as *const _ as *mut _, Ordering::Relaxed);
+    }
+
+    /// The label of the call holding the write lock, if it has one.
+    pub(crate) fn writer(&self) -> Option<&'static str> {
+        let writer = self.writer.load(Ordering::Relaxed);
+        // Only ever null or a label from `intern`
+        unsafe { writer.as_ref().copied() }
+    }
+
+    /// How long the lock has been write locked, if it currently is and wait times are being measured.
+    pub(crate) fn held_for(&self) -> Option<Duration> {
+        let at = self.held_at.load(Ordering::Relaxed);
+        if at == 0 {
+            return None;
+        }
+        #[cfg(feature = "time")]
+        return Some(
+            self.created
+                .elapsed()
+                .saturating_sub(Duration::from_nanos(at - 1)),
+        );
+        #[cfg(not(feature = "time"))]
+        None
+    }
+
+    #[cfg(feature = "time")]
+    pub(crate) fn overran(&self) {
+        if !self.enabled {
+            return;
+        }
+        self.overruns.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "time")]
+    pub(crate) fn lease_violated(&self) {
+        if !self.enabled {
+            return;
+        }
+        self.lease_violations.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl<T> Slock<T> {
+    /// Returns a snapshot of the lock's health, without acquiring it.
+    ///
+    /// Only the version is tracked if metrics were disabled with [`SlockBuilder::metrics`](crate::SlockBuilder::metrics).
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let lock = Slock::new(0i32);
+    /// lock.set(|v| v + 1).await;
+    ///
+    /// let metrics = lock.metrics();
+    /// assert_eq!(metrics.version, 1);
+    /// assert_eq!(metrics.acquisitions, 1);
+    /// # };
+    /// ```
+    pub fn metrics(&self) -> Metrics {
+        let counters = &self.lock.counters;
+        Metrics {
+            acquisitions: counters.acquisitions.load(Ordering::Relaxed),
+            wait_time: Duration::from_nanos(counters.wait_nanos.load(Ordering::Relaxed)),
+            timeouts: counters.timeouts.load(Ordering::Relaxed),
+            overruns: counters.overruns.load(Ordering::Relaxed),
+            lease_violations: counters.lease_violations.load(Ordering::Relaxed),
+            version: *self.lock.changes.borrow(),
+        }
+    }
+
+    /// Returns the metrics of calls made through [`Slock::instrument`], by label.
+    ///
+    /// Calls made without a label are only counted in [`Slock::metrics`].
+    pub fn callsite_metrics(&self) -> HashMap<&'static str, CallsiteMetrics> {
+        let callsites = self.lock.counters.callsites.lock();
+        let callsites = callsites.unwrap_or_else(|e| e.into_inner());
+        callsites
+            .iter()
+            .map(|(label, metrics)| (*label, *metrics))
+            .collect()
+    }
+}
@@ -0,0 +1,267 @@
+//! Operations spanning more than one lock.
+//!
+//! Locks are always acquired in the order of their address in memory,
+//! so two tasks working on the same locks can never wait on each other.
+
+use std::error::Error as StdError;
+
+use tokio::sync::RwLockReadGuard;
+
+use crate::{Error, Slock, SlockData, ValidationError};
+
+/// Returns `true` if two locks hold equal values.
+///
+/// Both values are read at the same moment, without cloning either.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let a = Slock::new(vec![1, 2, 3]);
+/// let b = Slock::new(vec![1, 2, 3]);
+/// assert!(slock::eq(&a, &b).await);
+/// # };
+/// ```
+pub async fn eq<T: PartialEq>(a: &Slock<T>, b: &Slock<T>) -> bool {
+    if a.id() == b.id() {
+        let data = a.read().await;
+        // Values like NaN don't equal themselves, so still compare.
+        #[allow(clippy::eq_op)]
+        return data.value == data.value;
+    }
+    let (first, second) = if a.id() < b.id() { (a, b) } else { (b, a) };
+    let first = first.read().await;
+    let second = second.read().await;
+    first.value == second.value
+}
+
+impl<T: PartialEq> Slock<T> {
+    /// Returns `true` if the lock holds a value equal to `other`.
+    pub async fn content_eq(&self, other: &T) -> bool {
+        let data = self.read().await;
+        data.value == *other
+    }
+}
+
+impl<T> Slock<T> {
+    /// Pair this lock with `other`, to read both values at the same moment.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let balance = Slock::new(100i64);
+    /// let pending = Slock::new(20i64);
+    ///
+    /// let (balance, pending) = balance.zip(&pending).get().await;
+    /// println!("{} available", balance - pending);
+    /// # };
+    /// ```
+    pub fn zip<'a, U>(&'a self, other: &'a Slock<U>) -> Zipped<'a, T, U> {
+        Zipped { a: self, b: other }
+    }
+}
+
+/// Two locks read together, created by [`Slock::zip`].
+///
+/// Both locks are held for reading at once, so the values are a consistent pair,
+/// never one from before a related update and one from after it.
+///
+/// # Panics
+///
+/// Reads panic if both halves are the same lock.
+pub struct Zipped<'a, A, B> {
+    a: &'a Slock<A>,
+    b: &'a Slock<B>,
+}
+
+impl<A, B> Zipped<'_, A, B> {
+    /// Extract something from both values.
+    pub async fn map<F, U>(&self, mapper: F) -> U
+    where
+        F: FnOnce(&A, &B) -> U,
+    {
+        let (a, b) = (self.a, self.b);
+        assert_ne!(a.id(), b.id(), "zip needs two different locks");
+        let (data_a, data_b) = if a.id() < b.id() {
+            let data_a = a.read().await;
+            (data_a, b.read().await)
+        } else {
+            let data_b = b.read().await;
+            (a.read().await, data_b)
+        };
+        mapper(&data_a.value, &data_b.value)
+    }
+}
+
+impl<A: Clone, B: Clone> Zipped<'_, A, B> {
+    /// Returns clones of both values.
+    pub async fn get_clone(&self) -> (A, B) {
+        self.map(|a, b| (a.clone(), b.clone())).await
+    }
+}
+
+impl<A: Copy, B: Copy> Zipped<'_, A, B> {
+    /// Returns copies of both values.
+    pub async fn get(&self) -> (A, B) {
+        self.map(|a, b| (*a, *b)).await
+    }
+}
+
+/// Update two related locks together, applying neither update unless both succeed.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let alice = Slock::new(100i64);
+/// let bob = Slock::new(20i64);
+///
+/// slock::two_phase(&alice, &bob)
+///     .prepare(|a, b| if *a >= 50 { Ok((a - 50, b + 50)) } else { Err("insufficient funds") })
+///     .commit()
+///     .await
+///     .unwrap();
+/// # };
+/// ```
+pub fn two_phase<'a, A, B>(a: &'a Slock<A>, b: &'a Slock<B>) -> TwoPhase<'a, A, B> {
+    TwoPhase { a, b }
+}
+
+/// Two locks waiting for an update, created by [`two_phase`].
+pub struct TwoPhase<'a, A, B> {
+    a: &'a Slock<A>,
+    b: &'a Slock<B>,
+}
+
+impl<'a, A, B> TwoPhase<'a, A, B> {
+    /// Compute both new values from the current ones, or an error to abandon the update.
+    pub fn prepare<F, E>(self, prepare: F) -> Prepared<'a, A, B, F>
+    where
+        F: FnOnce(&A, &B) -> Result<(A, B), E>,
+        E: Into<Box<dyn StdError + Send + Sync>>,
+    {
+        Prepared {
+            a: self.a,
+            b: self.b,
+            prepare,
+        }
+    }
+}
+
+/// A prepared update of two locks, applied by [`Prepared::commit`].
+#[must_use = "prepared updates do nothing until committed"]
+pub struct Prepared<'a, A, B, F> {
+    a: &'a Slock<A>,
+    b: &'a Slock<B>,
+    prepare: F,
+}
+
+impl<A, B, F, E> Prepared<'_, A, B, F>
+where
+    F: FnOnce(&A, &B) -> Result<(A, B), E>,
+    E: Into<Box<dyn StdError + Send + Sync>>,
+{
+    /// Write lock both locks, then store both new values if the update and both validators accept them.
+    ///
+    /// An error from the update is returned as [`Error::Validation`], and leaves both locks unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if both halves are the same lock.
+    pub async fn commit(self) -> Result<(), Error> {
+        let Prepared { a, b, prepare } = self;
+        assert_ne!(a.id(), b.id(), "two_phase needs two different locks");
+        let (mut data_a, mut data_b, _queued) = if a.id() < b.id() {
+            let queued = (a.enqueue(false).await?, b.enqueue(false).await?);
+            let data_a = a.write_as(None).await;
+            (data_a, b.write_as(None).await, queued)
+        } else {
+            let queued = (b.enqueue(false).await?, a.enqueue(false).await?);
+            let data_b = b.write_as(None).await;
+            (a.write_as(None).await, data_b, queued)
+        };
+
+        let (new_a, new_b) = a
+            .contain(async { prepare(&data_a.value, &data_b.value) })
+            .await?
+            .map_err(ValidationError::new)?;
+        if let Some(validator) = data_a.validator.as_ref() {
+            validator.check(&new_a)?;
+        }
+        if let Some(validator) = data_b.validator.as_ref() {
+            validator.check(&new_b)?;
+        }
+
+        data_a.value = new_a;
+        data_b.value = new_b;
+        a.publish(&mut data_a).await;
+        b.publish(&mut data_b).await;
+        Ok(())
+    }
+}
+
+/// Read several locks at once and pass their values to a closure, returning its result.
+///
+/// Any number of locks, each holding any type, can be listed.
+/// They're acquired in the same order as every other multi-lock operation, so joins can't deadlock each other.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let name = Slock::new("bob");
+/// let age = Slock::new(30u8);
+/// let tags = Slock::new(vec!["admin"]);
+///
+/// let summary = slock_join!(name, age, tags => |n, a, t| format!("{n} ({a}): {t:?}")).await;
+/// assert_eq!(summary, "bob (30): [\"admin\"]");
+/// # };
+/// ```
+///
+/// # Panics
+///
+/// The returned future panics if the same lock is listed twice.
+#[macro_export]
+macro_rules! slock_join {
+    ($($lock:expr),+ $(,)? => |$($value:ident),+ $(,)?| $body:expr) => {
+        async {
+            $( let mut $value = $crate::Joined::new(&$lock); )+
+            for id in $crate::Joined::<()>::order([$($value.id()),+]) {
+                $( $value.acquire_if(id).await; )+
+            }
+            $( let $value = $value.value(); )+
+            $body
+        }
+    };
+}
+
+/// One lock in a [`slock_join!`], waiting for its turn to be read.
+#[doc(hidden)]
+pub struct Joined<'a, T> {
+    lock: &'a Slock<T>,
+    guard: Option<RwLockReadGuard<'a, SlockData<T>>>,
+}
+
+#[doc(hidden)]
+impl<'a, T> Joined<'a, T> {
+    pub fn new(lock: &'a Slock<T>) -> Self {
+        Joined { lock, guard: None }
+    }
+
+    pub fn id(&self) -> usize {
+        self.lock.id()
+    }
+
+    /// The order to acquire locks with these ids in.
+    pub fn order<const N: usize>(mut ids: [usize; N]) -> [usize; N] {
+        ids.sort_unstable();
+        assert!(
+            ids.windows(2).all(|pair| pair[0] != pair[1]),
+            "slock_join needs every lock to be different"
+        );
+        ids
+    }
+
+    pub async fn acquire_if(&mut self, id: usize) {
+        if id == self.id() {
+            self.guard = Some(self.lock.read().await);
+        }
+    }
+
+    pub fn value(&self) -> &T {
+        &self.guard.as_ref().expect("every lock is acquired").value
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc;
+
+use crate::{Hook, SlockBuilder};
+
+/// How a lock runs its hook after each set. See [`SlockBuilder::hook_strategy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HookStrategy {
+    /// Run the hook under the write lock, before the set returns.
+    #[default]
+    Inline,
+    /// Run the hook on a copy of the new value in a newly spawned task, so the lock isn't held while it runs.
+    ///
+    /// Hooks for quick successive sets may run in any order.
+    Spawned,
+    /// Queue copies of each new value to a single task that runs the hook for each in turn.
+    ///
+    /// The lock isn't held while the hook runs, and the hook sees every value in order.
+    Dedicated,
+}
+
+type SharedHook<T> = Arc<Mutex<Option<Hook<T>>>>;
+
+/// Runs a lock's hook away from the lock, for the non-inline strategies.
+pub(crate) struct Notifier<T> {
+    strategy: HookStrategy,
+    snapshot: fn(&T) -> T,
+    /// Hands a new value to the hook, captured where the bounds for spawning are known.
+    dispatch: fn(&mut Notifier<T>, &T),
+    hook: SharedHook<T>,
+    /// Feeds the dedicated task, once it has started.
+    queue: Option<mpsc::UnboundedSender<T>>,
+}
+
+impl<T: Clone + Send + 'static> Notifier<T> {
+    fn new(strategy: HookStrategy) -> Self {
+        Notifier {
+            strategy,
+            snapshot: T::clone,
+            dispatch: Self::dispatch,
+            hook: Default::default(),
+            queue: None,
+        }
+    }
+}
+
+impl<T> Notifier<T> {
+    pub(crate) fn set_hook(&self, hook: Hook<T>) {
+        *self.hook.lock().unwrap() = Some(hook);
+    }
+
+    /// Passes a copy of the new value to the hook, if there is one.
+    pub(crate) fn notify(&mut self, value: &T) {
+        if self.hook.lock().unwrap().is_none() {
+            return;
+        }
+        (self.dispatch)(self, value);
+    }
+}
+
+impl<T: Send + 'static> Notifier<T> {
+    fn dispatch(&mut self, value: &T) {
+        // Spawning needs a runtime, so fall back to running inline without one
+        if tokio::runtime::Handle::try_current().is_err() {
+            call(&self.hook, value);
+            return;
+        }
+        let value = (self.snapshot)(value);
+        match self.strategy {
+            HookStrategy::Inline => call(&self.hook, &value),
+            HookStrategy::Spawned => {
+                let hook = self.hook.clone();
+                tokio::spawn(async move { call(&hook, &value) });
+            }
+            HookStrategy::Dedicated => {
+                let hook = &self.hook;
+                let queue = self.queue.get_or_insert_with(|| {
+                    let (sender, mut receiver) = mpsc::unbounded_channel();
+                    let hook = hook.clone();
+                    // Stops once the lock, and with it the sender, is dropped
+                    tokio::spawn(async move {
+                        while let Some(value) = receiver.recv().await {
+                            call(&hook, &value);
+                        }
+                    });
+                    sender
+                });
+                queue.send(value).ok();
+            }
+        }
+    }
+}
+
+fn call<T>(hook: &SharedHook<T>, value: &T) {
+    if let Some(hook) = hook.lock().unwrap().as_mut() {
+        hook(value);
+    }
+}
+
+impl<T: Clone + Send + 'static> SlockBuilder<T> {
+    /// Choose how the lock runs its hook. Defaults to [`HookStrategy::Inline`].
+    ///
+    /// The other strategies keep slow hooks from holding the lock, at the cost of copying each new value.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let events = Slock::builder()
+    ///     .hook_strategy(HookStrategy::Dedicated)
+    ///     .build(Vec::<String>::new());
+    /// events.hook(|v| println!("{} events", v.len())).await;
+    /// # };
+    /// ```
+    pub fn hook_strategy(mut self, strategy: HookStrategy) -> Self {
+        self.notifier = match strategy {
+            HookStrategy::Inline => None,
+            _ => Some(Notifier::new(strategy)),
+        };
+        self
+    }
+}
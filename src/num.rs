@@ -0,0 +1,84 @@
+use std::{error::Error, fmt};
+
+use crate::Slock;
+
+/// Returned when checked arithmetic on a lock would overflow.
+///
+/// The lock's value is left unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("arithmetic overflow")
+    }
+}
+
+impl Error for Overflow {}
+
+macro_rules! checked_ops {
+    ($int:ty; $($checked:ident, $saturating:ident, $wrapping:ident => $verb:literal;)*) => {
+        $(
+            #[doc = concat!($verb, " `n`, returning the new value, or `Err(Overflow)` without changing anything.")]
+            ///
+            /// If the lock's validator rejects the new value, nothing changes and the unchanged value is returned as `Ok`.
+            /// Compare it with the value you expected, or use [`Slock::try_set`] to get the rejection as an error.
+            pub async fn $checked(&self, n: $int) -> Result<$int, Overflow> {
+                let ((old, new), stored) = self
+                    .update_internal(|v| {
+                        let new = v.$checked(n);
+                        (new, (*v, new))
+                    })
+                    .await;
+                match new {
+                    Some(new) if stored => Ok(new),
+                    Some(_) => Ok(old),
+                    None => Err(Overflow),
+                }
+            }
+
+            #[doc = concat!($verb, " `n`, saturating at the numeric bounds. Returns the new value.")]
+            ///
+            /// If the lock's validator rejects the new value, nothing changes and the unchanged value is returned.
+            pub async fn $saturating(&self, n: $int) -> $int {
+                let ((old, new), stored) = self
+                    .update_internal(|v| {
+                        let new = v.$saturating(n);
+                        (Some(new), (*v, new))
+                    })
+                    .await;
+                if stored { new } else { old }
+            }
+
+            #[doc = concat!($verb, " `n`, wrapping around at the numeric bounds. Returns the new value.")]
+            ///
+            /// If the lock's validator rejects the new value, nothing changes and the unchanged value is returned.
+            pub async fn $wrapping(&self, n: $int) -> $int {
+                let ((old, new), stored) = self
+                    .update_internal(|v| {
+                        let new = v.$wrapping(n);
+                        (Some(new), (*v, new))
+                    })
+                    .await;
+                if stored { new } else { old }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_checked_math {
+    ($($int:ty),*) => {
+        $(
+            impl Slock<$int> {
+                checked_ops! {
+                    $int;
+                    checked_add, saturating_add, wrapping_add => "Add";
+                    checked_sub, saturating_sub, wrapping_sub => "Subtract";
+                    checked_mul, saturating_mul, wrapping_mul => "Multiply by";
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_math!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
@@ -0,0 +1,46 @@
+use crate::{Error, Slock};
+
+impl<T: Ord + Copy> Slock<T> {
+    /// Replace the value with `candidate` if `candidate` is greater.
+    ///
+    /// Returns `true` if the value changed.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let high_score = Slock::new(10i32);
+    /// assert!(high_score.fetch_max(12).await.unwrap());
+    /// assert!(!high_score.fetch_max(11).await.unwrap());
+    /// # };
+    /// ```
+    pub async fn fetch_max(&self, candidate: T) -> Result<bool, Error> {
+        self.replace_if(|v| candidate > *v, candidate).await
+    }
+
+    /// Replace the value with `candidate` if `candidate` is less.
+    ///
+    /// Returns `true` if the value changed.
+    pub async fn fetch_min(&self, candidate: T) -> Result<bool, Error> {
+        self.replace_if(|v| candidate < *v, candidate).await
+    }
+
+    /// Restrict the value to the range `min..=max`.
+    ///
+    /// Returns `true` if the value changed.
+    pub async fn clamp_in_place(&self, min: T, max: T) -> Result<bool, Error> {
+        self.update(|v| {
+            let clamped = (*v).clamp(min, max);
+            ((clamped != *v).then_some(clamped), ())
+        })
+        .await
+        .map(|(_, changed)| changed)
+    }
+
+    async fn replace_if<F>(&self, condition: F, candidate: T) -> Result<bool, Error>
+    where
+        F: FnOnce(&T) -> bool,
+    {
+        self.update(|v| (condition(v).then_some(candidate), ()))
+            .await
+            .map(|(_, changed)| changed)
+    }
+}
@@ -0,0 +1,66 @@
+use futures::future;
+
+use crate::Slock;
+
+impl<T> Slock<Vec<T>> {
+    /// Move the vector's contents into `n` independently locked chunks, in order.
+    ///
+    /// The chunks' lengths differ by at most one, and this lock is left empty until [`Slock::recombine`] is called.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let samples = Slock::new((0..1000).collect::<Vec<i32>>());
+    ///
+    /// let parts = samples.partition(4).await;
+    /// futures::future::join_all(parts.iter().map(|part| part.set(|mut v| {
+    ///     v.iter_mut().for_each(|x| *x *= 2);
+    ///     v
+    /// }))).await;
+    ///
+    /// samples.recombine(parts).await;
+    /// # };
+    /// ```
+    pub async fn partition(&self, n: usize) -> Vec<Slock<Vec<T>>> {
+        let n = n.max(1);
+        let mut whole = Vec::new();
+        self.set(|v| {
+            whole = v;
+            Vec::new()
+        })
+        .await;
+
+        let base = whole.len() / n;
+        let extra = whole.len() % n;
+        let mut parts = Vec::with_capacity(n);
+        // Split from the back so each chunk is only moved once
+        for i in (0..n).rev() {
+            let size = base + usize::from(i < extra);
+            let at = whole.len() - size;
+            parts.push(Slock::new(whole.split_off(at)));
+        }
+        parts.reverse();
+        parts
+    }
+
+    /// Move the contents of `parts` back into this lock, in order, after any values it already holds.
+    ///
+    /// The parts are left empty.
+    pub async fn recombine(&self, parts: Vec<Slock<Vec<T>>>) {
+        let chunks = future::join_all(parts.iter().map(|part| async move {
+            let mut chunk = Vec::new();
+            part.set(|v| {
+                chunk = v;
+                Vec::new()
+            })
+            .await;
+            chunk
+        }))
+        .await;
+        self.set(|mut v| {
+            v.reserve(chunks.iter().map(Vec::len).sum());
+            chunks.into_iter().for_each(|chunk| v.extend(chunk));
+            v
+        })
+        .await;
+    }
+}
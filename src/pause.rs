@@ -0,0 +1,57 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::sync::Notify;
+
+use crate::Slock;
+
+/// Holds writes back while a test has paused them.
+#[derive(Default)]
+pub(crate) struct Pause {
+    paused: AtomicBool,
+    resumed: Notify,
+}
+
+impl Pause {
+    /// Waits until writes aren't paused.
+    pub(crate) async fn wait(&self) {
+        while self.paused.load(Ordering::Acquire) {
+            // Registered before the check, so a resume in between isn't missed
+            let resumed = self.resumed.notified();
+            if !self.paused.load(Ordering::Acquire) {
+                break;
+            }
+            resumed.await;
+        }
+    }
+}
+
+impl<T> Slock<T> {
+    /// Make every write wait, without holding the lock, until [`Slock::resume_writes`] is called.
+    ///
+    /// Meant for tests, to reliably exercise code paths where a write is stuck waiting,
+    /// such as cancellation in `tokio::select!`. Reads are unaffected.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::time::Duration;
+    /// # async {
+    /// let lock = Slock::new(0i32);
+    /// lock.pause_writes();
+    ///
+    /// tokio::select! {
+    ///     _ = lock.set(|v| v + 1) => unreachable!("writes are paused"),
+    ///     _ = tokio::time::sleep(Duration::from_millis(10)) => {}
+    /// }
+    ///
+    /// lock.resume_writes();
+    /// # };
+    /// ```
+    pub fn pause_writes(&self) {
+        self.lock.pause.paused.store(true, Ordering::Release);
+    }
+
+    /// Let writes held back by [`Slock::pause_writes`] continue.
+    pub fn resume_writes(&self) {
+        self.lock.pause.paused.store(false, Ordering::Release);
+        self.lock.pause.resumed.notify_waiters();
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{Error, Slock};
+
+/// A lazily evaluated chain of read transformations, created by [`Slock::pipe`].
+///
+/// Nothing is read until [`Pipe::resolve`] is awaited,
+/// at which point the whole chain runs under a single read acquisition.
+#[must_use = "pipes do nothing until resolved"]
+pub struct Pipe<'a, T, F> {
+    lock: &'a Slock<T>,
+    mapper: F,
+}
+
+impl<T> Slock<T> {
+    /// Start a chain of read transformations.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let words = Slock::new(vec!["a", "bb", "ccc"]);
+    /// let total = words
+    ///     .pipe(|v| v.iter().map(|w| w.len()).collect::<Vec<_>>())
+    ///     .pipe(|lengths| lengths.into_iter().sum::<usize>())
+    ///     .resolve()
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(total, 6);
+    /// # };
+    /// ```
+    pub fn pipe<F, U>(&self, mapper: F) -> Pipe<'_, T, F>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        Pipe { lock: self, mapper }
+    }
+}
+
+impl<'a, T, F, U> Pipe<'a, T, F>
+where
+    F: FnOnce(&T) -> U,
+{
+    /// Add another transformation to the end of the chain.
+    pub fn pipe<G, W>(self, next: G) -> Pipe<'a, T, impl FnOnce(&T) -> W>
+    where
+        G: FnOnce(U) -> W,
+    {
+        let mapper = self.mapper;
+        Pipe {
+            lock: self.lock,
+            mapper: move |v: &T| next(mapper(v)),
+        }
+    }
+
+    /// Run the whole chain under a single read acquisition.
+    pub async fn resolve(self) -> Result<U, Error> {
+        self.lock.map(self.mapper).await
+    }
+}
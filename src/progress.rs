@@ -0,0 +1,99 @@
+use futures::{Stream, StreamExt};
+
+use crate::Slock;
+
+/// ## Progress
+///
+/// Progress of a long running job, shared between the tasks doing it and the ones reporting on it.
+/// ```rust
+/// # use slock::*;
+/// # use futures::StreamExt;
+/// # async {
+/// let progress = Progress::new_progress(200);
+/// let mut updates = Box::pin(progress.updates().await);
+///
+/// progress.set_stage("downloading").await;
+/// progress.advance(50).await;
+/// assert_eq!(progress.fraction().await, 0.25);
+///
+/// while let Some(state) = updates.next().await {
+///     println!("{:?}: {:.0}%", state.stage, state.fraction() * 100.0);
+/// #   break;
+/// }
+/// # };
+/// ```
+pub type Progress = Slock<ProgressState>;
+
+/// The state behind a [`Progress`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProgressState {
+    /// Units of work done so far.
+    pub done: u64,
+    /// Units of work in total.
+    pub total: u64,
+    /// The name of the current stage, if one was set.
+    pub stage: Option<String>,
+}
+
+impl ProgressState {
+    /// The fraction of the work done, from 0 to 1. A job with no work in total counts as done.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.done as f64 / self.total as f64).min(1.0)
+    }
+
+    /// Returns `true` once all of the work is done.
+    pub fn is_finished(&self) -> bool {
+        self.done >= self.total
+    }
+}
+
+impl Slock<ProgressState> {
+    /// Start tracking a job with `total` units of work.
+    pub fn new_progress(total: u64) -> Self {
+        Slock::new(ProgressState {
+            total,
+            ..Default::default()
+        })
+    }
+
+    /// Mark `n` more units of work as done.
+    pub async fn advance(&self, n: u64) {
+        self.set(|mut state| {
+            state.done = state.done.saturating_add(n);
+            state
+        })
+        .await;
+    }
+
+    /// Move on to a new named stage, without resetting the work done.
+    pub async fn set_stage(&self, name: impl Into<String>) {
+        let name = name.into();
+        self.set(|mut state| {
+            state.stage = Some(name);
+            state
+        })
+        .await;
+    }
+
+    /// Change the amount of work in total, such as once it's known.
+    pub async fn set_total(&self, total: u64) {
+        self.set(|mut state| {
+            state.total = total;
+            state
+        })
+        .await;
+    }
+
+    /// The fraction of the work done, from 0 to 1.
+    pub async fn fraction(&self) -> f64 {
+        self.peek(ProgressState::fraction).await
+    }
+
+    /// A stream of every update from now on. See [`Slock::changes`].
+    pub async fn updates(&self) -> impl Stream<Item = ProgressState> {
+        self.changes().await.map(|(_, state)| state)
+    }
+}
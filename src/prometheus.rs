@@ -0,0 +1,120 @@
+use std::sync::Mutex;
+
+use prometheus::{
+    core::{Collector, Desc},
+    proto::MetricFamily,
+    IntCounterVec, IntGaugeVec, Opts,
+};
+
+use crate::{Metrics, Slock};
+
+type Source = Box<dyn Fn() -> Metrics + Send + Sync>;
+
+/// Exports the [`Metrics`] of named locks to a Prometheus registry.
+///
+/// Every metric is labelled with `lock="<name>"`.
+/// ```rust
+/// # use slock::*;
+/// let config = Slock::new(String::new());
+///
+/// let collector = SlockCollector::new();
+/// collector.register("config", &config);
+/// prometheus::register(Box::new(collector)).unwrap();
+/// ```
+pub struct SlockCollector {
+    locks: Mutex<Vec<(String, Source)>>,
+    acquisitions: IntCounterVec,
+    wait_seconds: prometheus::CounterVec,
+    timeouts: IntCounterVec,
+    version: IntGaugeVec,
+}
+
+impl SlockCollector {
+    /// Create a collector with no locks registered.
+    pub fn new() -> Self {
+        let labels = &["lock"];
+        Self {
+            locks: Mutex::new(Vec::new()),
+            acquisitions: IntCounterVec::new(
+                Opts::new("slock_acquisitions_total", "Times the lock was acquired."),
+                labels,
+            )
+            .unwrap(),
+            wait_seconds: prometheus::CounterVec::new(
+                Opts::new(
+                    "slock_wait_seconds_total",
+                    "Time spent waiting to acquire the lock.",
+                ),
+                labels,
+            )
+            .unwrap(),
+            timeouts: IntCounterVec::new(
+                Opts::new("slock_timeouts_total", "Lock operations that timed out."),
+                labels,
+            )
+            .unwrap(),
+            version: IntGaugeVec::new(
+                Opts::new("slock_version", "Times the lock has been set."),
+                labels,
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Export a lock's metrics under `name`.
+    ///
+    /// The collector keeps the lock alive.
+    /// Locks registered under the same name share one series, whose counters follow the highest of their totals.
+    pub fn register<T: Send + 'static>(&self, name: impl Into<String>, lock: &Slock<T>) {
+        let lock = lock.clone();
+        self.locks
+            .lock()
+            .unwrap()
+            .push((name.into(), Box::new(move || lock.metrics())));
+    }
+}
+
+impl Default for SlockCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector for SlockCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        [
+            self.acquisitions.desc(),
+            self.wait_seconds.desc(),
+            self.timeouts.desc(),
+            self.version.desc(),
+        ]
+        .concat()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        for (name, source) in self.locks.lock().unwrap().iter() {
+            let metrics = source();
+            let labels = &[name.as_str()];
+
+            // Counters can only go up, so catch them up to the lock's own totals.
+            // Locks registered under the same name share counters, which then follow the busiest of them.
+            let acquisitions = self.acquisitions.with_label_values(labels);
+            acquisitions.inc_by(metrics.acquisitions.saturating_sub(acquisitions.get()));
+            let wait_seconds = self.wait_seconds.with_label_values(labels);
+            wait_seconds.inc_by((metrics.wait_time.as_secs_f64() - wait_seconds.get()).max(0.0));
+            let timeouts = self.timeouts.with_label_values(labels);
+            timeouts.inc_by(metrics.timeouts.saturating_sub(timeouts.get()));
+
+            self.version
+                .with_label_values(labels)
+                .set(metrics.version as i64);
+        }
+        [
+            self.acquisitions.collect(),
+            self.wait_seconds.collect(),
+            self.timeouts.collect(),
+            self.version.collect(),
+        ]
+        .concat()
+    }
+}
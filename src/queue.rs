@@ -0,0 +1,57 @@
+use tokio::sync::{Semaphore, SemaphorePermit, TryAcquireError};
+
+use crate::{Error, Slock};
+
+/// What a write does when the lock's write queue is full. See [`SlockBuilder::write_queue`](crate::SlockBuilder::write_queue).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhenFull {
+    /// Wait for room in the queue.
+    Wait,
+    /// Fail with [`Error::QueueFull`] straight away.
+    Reject,
+}
+
+/// Bounds the number of writes waiting on, or holding, a lock.
+pub(crate) struct WriteQueue {
+    permits: Semaphore,
+    when_full: WhenFull,
+}
+
+impl WriteQueue {
+    pub(crate) fn new(capacity: usize, when_full: WhenFull) -> Self {
+        Self {
+            permits: Semaphore::new(capacity.max(1)),
+            when_full,
+        }
+    }
+}
+
+impl<T> Slock<T> {
+    /// Create a new Slock which allows at most `capacity` writes to wait on it at once.
+    ///
+    /// Further writes wait for room, giving producers back-pressure when a slow hook or validator limits write throughput.
+    /// Use [`SlockBuilder::write_queue`](crate::SlockBuilder::write_queue) to reject them instead.
+    pub fn with_write_queue(value: T, capacity: usize) -> Self {
+        Slock::builder()
+            .write_queue(capacity, WhenFull::Wait)
+            .build(value)
+    }
+
+    /// Takes a place in the write queue, if the lock has one.
+    ///
+    /// `reject` allows a full queue to fail the write instead of waiting.
+    pub(crate) async fn enqueue(&self, reject: bool) -> Result<Option<SemaphorePermit<'_>>, Error> {
+        let Some(queue) = &self.lock.write_queue else {
+            return Ok(None);
+        };
+        if reject && queue.when_full == WhenFull::Reject {
+            return match queue.permits.try_acquire() {
+                Ok(permit) => Ok(Some(permit)),
+                Err(TryAcquireError::NoPermits) => Err(Error::QueueFull),
+                Err(TryAcquireError::Closed) => unreachable!("the queue is never closed"),
+            };
+        }
+        let permit = queue.permits.acquire().await;
+        Ok(Some(permit.expect("the queue is never closed")))
+    }
+}
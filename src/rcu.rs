@@ -0,0 +1,43 @@
+use crate::{Error, Slock};
+
+impl<T: Clone> Slock<T> {
+    /// Compute a new value from a snapshot, without holding the lock, then store it if nothing else was stored meanwhile.
+    ///
+    /// If another write lands while `compute` runs, the snapshot is retaken and `compute` runs again,
+    /// so it should be free of side effects. Cheap to snapshot values, like `Arc`s, work best.
+    ///
+    /// Returns [`Error::Validation`] if the lock's validator rejects the new value.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::sync::Arc;
+    /// # fn reindex(words: &[String]) -> Vec<String> { words.to_vec() }
+    /// # async {
+    /// let index = Slock::new(Arc::new(vec![String::from("slock")]));
+    /// index.rcu_update(|words| Arc::new(reindex(words))).await.unwrap();
+    /// # };
+    /// ```
+    pub async fn rcu_update<F>(&self, mut compute: F) -> Result<(), Error>
+    where
+        F: FnMut(&T) -> T,
+    {
+        loop {
+            let (version, snapshot) = {
+                let data = self.read().await;
+                (data.version, data.value.clone())
+            };
+            let new = compute(&snapshot);
+
+            let _queued = self.enqueue(false).await?;
+            let mut data = self.write_as(None).await;
+            if data.version != version {
+                continue;
+            }
+            if let Some(validator) = data.validator.as_ref() {
+                validator.check(&new)?;
+            }
+            data.value = new;
+            self.publish(&mut data).await;
+            return Ok(());
+        }
+    }
+}
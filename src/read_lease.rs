@@ -0,0 +1,131 @@
+use std::{
+    future::Future,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::{sync::RwLockReadGuard, task::JoinHandle};
+
+use crate::{
+    event::{self, SlockEvent},
+    metrics::Counters,
+    time::{sleep, timeout},
+    Error, Slock, SlockData,
+};
+
+/// A read of a lock which may be held across awaits, for up to a declared limit. Created by [`Slock::read_for`].
+///
+/// Derefs to the lock's value. Holding it past the limit is counted in
+/// [`Metrics::lease_violations`](crate::Metrics::lease_violations) and reported as [`SlockEvent::ReadOverran`]
+/// as soon as the limit runs out, or when dropped if it was taken outside of a tokio runtime.
+pub struct ReadLease<'a, T> {
+    lock: &'a Slock<T>,
+    guard: RwLockReadGuard<'a, SlockData<T>>,
+    acquired: Instant,
+    limit: Duration,
+    /// Set by whichever of the watch and the drop gets to report the lease first.
+    reported: Arc<AtomicBool>,
+    watch: Option<JoinHandle<()>>,
+}
+
+impl<T> Slock<T> {
+    /// Read the lock for up to `limit`, keeping the read across awaits.
+    ///
+    /// Writers wait for the lease to be dropped, so keep the limit short.
+    /// Use [`ReadLease::within`] to cancel work that would run past it.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::time::Duration;
+    /// # async fn send(_: &str) {}
+    /// # async {
+    /// let motd = Slock::new(String::from("hello"));
+    ///
+    /// let lease = motd.read_for(Duration::from_millis(100)).await;
+    /// if lease.within(send(&lease)).await.is_err() {
+    ///     println!("gave up sending the motd");
+    /// }
+    /// # };
+    /// ```
+    pub async fn read_for(&self, limit: Duration) -> ReadLease<'_, T> {
+        let guard = self.read().await;
+        let acquired = Instant::now();
+        let reported = Arc::new(AtomicBool::new(false));
+        let watch = tokio::runtime::Handle::try_current().ok().map(|runtime| {
+            let counters = self.lock.counters.clone();
+            let name = self.lock.name.clone();
+            let reported = reported.clone();
+            runtime.spawn(async move {
+                sleep(limit).await;
+                if !reported.swap(true, Ordering::AcqRel) {
+                    report(&counters, name.as_deref(), acquired.elapsed(), limit);
+                }
+            })
+        });
+        ReadLease {
+            lock: self,
+            guard,
+            acquired,
+            limit,
+            reported,
+            watch,
+        }
+    }
+}
+
+fn report(counters: &Counters, lock: Option<&str>, held_for: Duration, limit: Duration) {
+    counters.lease_violated();
+    event::emit(SlockEvent::ReadOverran {
+        lock,
+        held_for,
+        limit,
+    });
+}
+
+impl<T> ReadLease<'_, T> {
+    /// How long is left before the lease overruns.
+    pub fn remaining(&self) -> Duration {
+        self.limit.saturating_sub(self.acquired.elapsed())
+    }
+
+    /// Returns `true` if the lease has been held for longer than its limit.
+    pub fn is_expired(&self) -> bool {
+        self.acquired.elapsed() > self.limit
+    }
+
+    /// Run `future` for no longer than the lease has left, returning [`Error::Timeout`] if it is cut short.
+    pub async fn within<F: Future>(&self, future: F) -> Result<F::Output, Error> {
+        timeout(self.remaining(), future).await.map_err(|_| {
+            self.lock.lock.counters.timed_out();
+            Error::Timeout
+        })
+    }
+}
+
+impl<T> Deref for ReadLease<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard.value
+    }
+}
+
+impl<T> Drop for ReadLease<'_, T> {
+    fn drop(&mut self) {
+        if let Some(watch) = self.watch.take() {
+            watch.abort();
+        }
+        let held_for = self.acquired.elapsed();
+        if !self.reported.swap(true, Ordering::AcqRel) && held_for > self.limit {
+            report(
+                &self.lock.lock.counters,
+                self.lock.name(),
+                held_for,
+                self.limit,
+            );
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::{
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use tokio::sync::Mutex;
+
+use crate::Slock;
+
+/// Coordinates [`Slock::recompute_once`] callers.
+#[derive(Default)]
+pub(crate) struct Recompute {
+    /// How many recomputations have finished.
+    generation: AtomicU64,
+    running: Mutex<()>,
+}
+
+impl<T: Clone> Slock<T> {
+    /// Replace the value with the result of an expensive async computation,
+    /// coalescing concurrent callers onto a single computation.
+    ///
+    /// If a computation is already running, this waits for it and returns its result instead of starting another.
+    /// The lock is not held while `compute` runs.
+    /// ```rust
+    /// # use slock::*;
+    /// # async fn rebuild_index(old: Vec<u32>) -> Vec<u32> { old }
+    /// # async {
+    /// let index = Slock::new(Vec::<u32>::new());
+    ///
+    /// // Only one rebuild runs
+    /// tokio::join!(
+    ///     index.recompute_once(rebuild_index),
+    ///     index.recompute_once(rebuild_index),
+    /// );
+    /// # };
+    /// ```
+    pub async fn recompute_once<F, Fut>(&self, compute: F) -> T
+    where
+        F: FnOnce(T) -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let recompute = &self.lock.recompute;
+        let arrived = recompute.generation.load(Ordering::Acquire);
+        let _running = recompute.running.lock().await;
+        if recompute.generation.load(Ordering::Acquire) != arrived {
+            // Someone else finished a computation while we waited
+            return self.get_clone().await;
+        }
+
+        let new = compute(self.get_clone().await).await;
+        self.set(|_| new.clone()).await;
+        recompute.generation.fetch_add(1, Ordering::Release);
+        new
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::VecDeque;
+
+use crate::{time::Instant, Slock};
+
+/// A single recorded state of a lock.
+#[derive(Debug, Clone)]
+pub struct Frame<T> {
+    /// The lock's version when this value was stored.
+    pub version: u64,
+    /// When this value was stored.
+    pub at: Instant,
+    /// The value that was stored.
+    pub value: T,
+}
+
+/// A bounded buffer of recorded frames, oldest first.
+pub(crate) struct History<T> {
+    capacity: usize,
+    frames: VecDeque<Frame<T>>,
+    snapshot: fn(&T) -> T,
+}
+
+impl<T: Clone> History<T> {
+    /// An empty history keeping at most `capacity` frames.
+    pub(crate) fn new(capacity: usize) -> Self {
+        History {
+            capacity: capacity.max(1),
+            frames: VecDeque::new(),
+            snapshot: T::clone,
+        }
+    }
+}
+
+impl<T> History<T> {
+    pub(crate) fn push(&mut self, version: u64, value: &T) {
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(Frame {
+            version,
+            at: Instant::now(),
+            value: (self.snapshot)(value),
+        });
+    }
+}
+
+impl<T: Clone> Slock<T> {
+    /// Start recording every value this lock takes, keeping the most recent `capacity` frames.
+    ///
+    /// The current value is recorded immediately. Calling this again restarts the recording.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let lock = Slock::new(0i32);
+    /// lock.record(16).await;
+    /// lock.set(|v| v + 1).await;
+    ///
+    /// let values: Vec<i32> = lock.timeline().await.into_iter().map(|f| f.value).collect();
+    /// assert_eq!(values, vec![0, 1]);
+    /// # };
+    /// ```
+    pub async fn record(&self, capacity: usize) {
+        let mut data = self.write().await;
+        let mut history = History::new(capacity);
+        history.push(data.version, &data.value);
+        data.history = Some(history);
+    }
+
+    /// Returns every recorded frame, oldest first.
+    ///
+    /// Empty if the lock is not being recorded.
+    pub async fn timeline(&self) -> Vec<Frame<T>> {
+        let data = self.read().await;
+        data.history
+            .as_ref()
+            .map(|history| history.frames.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns up to `n` of the most recently recorded values, oldest first.
+    ///
+    /// Empty if the lock is not being recorded.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let cpu = Slock::new(0u8);
+    /// cpu.record(60).await;
+    /// for sample in [12, 40, 33] {
+    ///     cpu.set(|_| sample).await;
+    /// }
+    ///
+    /// assert_eq!(cpu.recent(2).await, vec![40, 33]);
+    /// # };
+    /// ```
+    pub async fn recent(&self, n: usize) -> Vec<T> {
+        let data = self.read().await;
+        let Some(history) = data.history.as_ref() else {
+            return Vec::new();
+        };
+        let skip = history.frames.len().saturating_sub(n);
+        history
+            .frames
+            .iter()
+            .skip(skip)
+            .map(|frame| frame.value.clone())
+            .collect()
+    }
+
+    /// Returns the value the lock held at a given moment.
+    ///
+    /// `None` if that moment is older than the oldest recorded frame.
+    pub async fn replay_at(&self, at: Instant) -> Option<T> {
+        let data = self.read().await;
+        let history = data.history.as_ref()?;
+        history
+            .frames
+            .iter()
+            .take_while(|frame| frame.at <= at)
+            .last()
+            .map(|frame| frame.value.clone())
+    }
+}
@@ -0,0 +1,70 @@
+use std::future::Future;
+
+use crate::{Error, Slock};
+
+impl<T, E> Slock<Result<T, E>> {
+    /// Returns `true` if the lock holds an `Ok` value.
+    pub async fn is_ok(&self) -> bool {
+        self.peek(|v| v.is_ok()).await
+    }
+
+    /// Returns `true` if the lock holds an `Err` value.
+    pub async fn is_err(&self) -> bool {
+        self.peek(|v| v.is_err()).await
+    }
+
+    /// Extract something from the `Ok` value, if there is one.
+    ///
+    /// Like [`Slock::map`], returns an error if `mapper` panics or times out.
+    pub async fn map_ok<F, U>(&self, mapper: F) -> Result<Option<U>, Error>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        self.map(|v| v.as_ref().ok().map(mapper)).await
+    }
+
+    /// Replace the lock's value with `Ok(value)`.
+    pub async fn set_ok(&self, value: T) {
+        self.set(|_| Ok(value)).await;
+    }
+
+    /// Replace the lock's value with `Err(error)`.
+    pub async fn set_err(&self, error: E) {
+        self.set(|_| Err(error)).await;
+    }
+
+    /// Run `operation` up to `attempts` times, stopping at the first success, and store the final outcome.
+    ///
+    /// The lock is not held while `operation` runs. Returns `true` if the stored outcome is `Ok`.
+    /// ```rust
+    /// # use slock::*;
+    /// # async fn fetch_config() -> Result<String, String> { Ok(String::new()) }
+    /// # async {
+    /// let latest: Slock<Result<String, String>> = Slock::new(Err("not loaded".into()));
+    /// let loaded = latest.retry_with(3, fetch_config).await;
+    /// # };
+    /// ```
+    pub async fn retry_with<F, Fut>(&self, attempts: usize, mut operation: F) -> bool
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut outcome = operation().await;
+        for _ in 1..attempts {
+            if outcome.is_ok() {
+                break;
+            }
+            outcome = operation().await;
+        }
+        let is_ok = outcome.is_ok();
+        self.set(|_| outcome).await;
+        is_ok
+    }
+}
+
+impl<T: Clone, E> Slock<Result<T, E>> {
+    /// Returns a clone of the `Ok` value, if there is one.
+    pub async fn ok_clone(&self) -> Option<T> {
+        self.peek(|v| v.as_ref().ok().cloned()).await
+    }
+}
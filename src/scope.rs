@@ -0,0 +1,101 @@
+use std::future::Future;
+
+use tokio::task::JoinSet;
+
+use crate::{Error, Metrics, Slock};
+
+/// A set of tasks sharing a lock, which can't outlive the scope. Created by [`Slock::scope`].
+///
+/// Dropping the scope cancels every task still running.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let hits = Slock::new(0u32);
+///
+/// let mut scope = hits.scope();
+/// for _ in 0..4 {
+///     scope.spawn(|hits| async move { hits.set(|v| v + 1).await });
+/// }
+/// scope.join().await;
+/// # };
+/// ```
+pub struct SlockScope<T> {
+    lock: Slock<T>,
+    tasks: JoinSet<()>,
+}
+
+impl<T> Slock<T> {
+    /// Start a scope for spawning tasks that each own a handle to this lock.
+    pub fn scope(&self) -> SlockScope<T> {
+        SlockScope {
+            lock: self.clone(),
+            tasks: JoinSet::new(),
+        }
+    }
+}
+
+impl<T: Send + 'static> SlockScope<T> {
+    /// Spawn a task with its own handle to the lock.
+    pub fn spawn<F, Fut>(&mut self, task: F) -> &mut Self
+    where
+        F: FnOnce(Slock<T>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.tasks.spawn(task(self.lock.clone()));
+        self
+    }
+
+    /// Wait for every task in the scope to finish.
+    ///
+    /// If a task panicked, the panic is resumed here.
+    pub async fn join(mut self) {
+        while let Some(result) = self.tasks.join_next().await {
+            if let Err(e) = result {
+                if e.is_panic() {
+                    std::panic::resume_unwind(e.into_panic());
+                }
+            }
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> Slock<T> {
+    /// Spawn a task into `join_set` for each setter, all applied to this lock concurrently.
+    ///
+    /// Each task finishes with the lock's [`Metrics`] as of its own write, or the error that stopped it.
+    /// Returns the number of tasks spawned.
+    /// ```rust
+    /// # use slock::*;
+    /// # use tokio::task::JoinSet;
+    /// # async {
+    /// let total = Slock::new(0u64);
+    /// let mut tasks = JoinSet::new();
+    /// total.spawn_mutators(&mut tasks, (1..=10).map(|n| move |v: u64| v + n));
+    ///
+    /// while let Some(result) = tasks.join_next().await {
+    ///     let metrics = result.unwrap().unwrap();
+    ///     println!("write {} done", metrics.version);
+    /// }
+    /// # };
+    /// ```
+    pub fn spawn_mutators<I, F>(
+        &self,
+        join_set: &mut JoinSet<Result<Metrics, Error>>,
+        setters: I,
+    ) -> usize
+    where
+        I: IntoIterator<Item = F>,
+        F: FnOnce(T) -> T + Send + 'static,
+    {
+        let mut spawned = 0;
+        for setter in setters {
+            let lock = self.clone();
+            join_set.spawn(async move {
+                lock.try_set(setter).await?;
+                Ok(lock.metrics())
+            });
+            spawned += 1;
+        }
+        spawned
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    collections::BTreeSet,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use tokio::sync::watch;
+
+use crate::Slock;
+
+/// Pushes to a `Slock<Vec<T>>` in the order they were started, created by [`Slock::ordered`].
+///
+/// Each push takes a sequence number when it's called, and waits for every earlier push to finish before committing.
+/// Clones share the same sequence, so give one to each producer.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let log = Slock::new(Vec::new());
+/// let ordered = log.ordered();
+///
+/// let first = ordered.push("connected");
+/// let second = ordered.push("authenticated");
+/// futures::join!(second, first);
+/// assert_eq!(log.get_clone().await, vec!["connected", "authenticated"]);
+/// # };
+/// ```
+pub struct OrderedPush<T> {
+    lock: Slock<Vec<T>>,
+    sequence: Arc<Sequence>,
+}
+
+struct Sequence {
+    /// The sequence number given to the next push.
+    next: AtomicU64,
+    /// The sequence number whose turn it is to commit.
+    turn: watch::Sender<u64>,
+    /// Pushes dropped before their turn, to be skipped over.
+    abandoned: Mutex<BTreeSet<u64>>,
+}
+
+impl Sequence {
+    /// Passes the turn on from `seq`, skipping abandoned pushes. Must be `seq`'s turn.
+    fn advance(&self, abandoned: &mut BTreeSet<u64>, seq: u64) {
+        let mut next = seq + 1;
+        while abandoned.remove(&next) {
+            next += 1;
+        }
+        self.turn.send_replace(next);
+    }
+}
+
+/// A push's place in the sequence, which is given up if dropped before committing.
+struct Ticket<'a> {
+    sequence: &'a Sequence,
+    seq: u64,
+    done: bool,
+}
+
+impl Ticket<'_> {
+    fn commit(&mut self) {
+        let mut abandoned = self.sequence.abandoned.lock().unwrap();
+        self.sequence.advance(&mut abandoned, self.seq);
+        self.done = true;
+    }
+}
+
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let mut abandoned = self.sequence.abandoned.lock().unwrap();
+        if *self.sequence.turn.borrow() == self.seq {
+            self.sequence.advance(&mut abandoned, self.seq);
+        } else {
+            abandoned.insert(self.seq);
+        }
+    }
+}
+
+impl<T> Slock<Vec<T>> {
+    /// Start pushing to this vec in a guaranteed order. See [`OrderedPush`].
+    pub fn ordered(&self) -> OrderedPush<T> {
+        OrderedPush {
+            lock: self.clone(),
+            sequence: Arc::new(Sequence {
+                next: AtomicU64::new(0),
+                turn: watch::channel(0).0,
+                abandoned: Mutex::new(BTreeSet::new()),
+            }),
+        }
+    }
+}
+
+impl<T> OrderedPush<T> {
+    /// The lock being pushed to.
+    pub fn lock(&self) -> &Slock<Vec<T>> {
+        &self.lock
+    }
+
+    /// Push a value after every push started before this call.
+    ///
+    /// The value's place is taken when this is called, not when the future is first polled.
+    /// Dropping the future before it completes gives up its place without holding up later pushes.
+    pub fn push(&self, value: T) -> impl Future<Output = ()> + '_ {
+        let mut ticket = Ticket {
+            sequence: &self.sequence,
+            seq: self.sequence.next.fetch_add(1, Ordering::Relaxed),
+            done: false,
+        };
+        async move {
+            let mut turn = self.sequence.turn.subscribe();
+            while *turn.borrow_and_update() != ticket.seq {
+                // The sender lives as long as `self`
+                turn.changed().await.ok();
+            }
+            self.lock.push(value).await;
+            ticket.commit();
+        }
+    }
+}
+
+impl<T> Clone for OrderedPush<T> {
+    fn clone(&self) -> Self {
+        Self {
+            lock: self.lock.clone(),
+            sequence: self.sequence.clone(),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{
+    event::{self, SlockEvent},
+    Error, Slock,
+};
+
+/// A handle that reads its own writes, created by [`Slock::session`].
+///
+/// Every read through the session waits until the lock has caught up with the session's last write,
+/// so a task never sees a value older than one it wrote itself.
+pub struct Session<T> {
+    lock: Slock<T>,
+    /// The version of the session's last write.
+    written: AtomicU64,
+}
+
+impl<T> Slock<T> {
+    /// Start a session with read-your-writes consistency.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let stage = Slock::new(0u32);
+    /// let session = stage.session();
+    ///
+    /// session.set(|v| v + 1).await;
+    /// assert!(session.map(|v| *v).await.unwrap() >= 1);
+    /// # };
+    /// ```
+    pub fn session(&self) -> Session<T> {
+        Session {
+            lock: self.clone(),
+            written: AtomicU64::new(0),
+        }
+    }
+}
+
+impl<T> Session<T> {
+    /// The lock this session reads and writes.
+    pub fn lock(&self) -> &Slock<T> {
+        &self.lock
+    }
+
+    /// The version of the session's last write, or `0` if it hasn't written.
+    pub fn version(&self) -> u64 {
+        self.written.load(Ordering::Acquire)
+    }
+
+    /// Set the lock, remembering the write.
+    ///
+    /// A failed write is reported to the [`set_event_logger`](crate::set_event_logger) logger, like [`Slock::set`].
+    pub async fn set<F>(&self, setter: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        if let Err(error) = self.try_set(setter).await {
+            event::emit(SlockEvent::WriteDropped {
+                lock: self.lock.name(),
+                label: None,
+                error: &error,
+            });
+        }
+    }
+
+    /// Like [`Session::set`], but returns any error from [`Slock::try_set`].
+    pub async fn try_set<F>(&self, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        self.lock.try_set(setter).await?;
+        // Published before the write lock is released, so this is at least our write
+        let version = *self.lock.lock.changes.borrow();
+        self.written.fetch_max(version, Ordering::AcqRel);
+        Ok(())
+    }
+
+    /// Wait until the lock has caught up with the session's last write.
+    pub async fn caught_up(&self) {
+        let written = self.version();
+        let mut receiver = self.lock.lock.changes.subscribe();
+        while *receiver.borrow_and_update() < written {
+            receiver.changed().await.ok();
+        }
+    }
+
+    /// Like [`Slock::map`], once the lock has caught up with the session.
+    pub async fn map<F, U>(&self, mapper: F) -> Result<U, Error>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        self.caught_up().await;
+        self.lock.map(mapper).await
+    }
+}
+
+impl<T: Clone> Session<T> {
+    /// Like [`Slock::get_clone`], once the lock has caught up with the session.
+    pub async fn get_clone(&self) -> T {
+        self.caught_up().await;
+        self.lock.get_clone().await
+    }
+}
+
+impl<T: Copy> Session<T> {
+    /// Like [`Slock::get`], once the lock has caught up with the session.
+    pub async fn get(&self) -> T {
+        self.caught_up().await;
+        self.lock.get().await
+    }
+}
@@ -0,0 +1,89 @@
+use crate::Slock;
+
+/// An async event, counting every time it is notified. Created by [`Slock::signal`] or [`Signal::new`].
+///
+/// Each notification sets the underlying `Slock<()>`, so the count is the lock's version and hooks on the lock run on every notification.
+/// Waiters wait for a count rather than a flag, so a notification can't be missed between checking and waiting.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let reloaded = Signal::new();
+///
+/// let waiter = reloaded.clone();
+/// tokio::spawn(async move {
+///     loop {
+///         waiter.wait().await;
+///         println!("config reloaded");
+///     }
+/// });
+///
+/// reloaded.notify().await;
+/// assert_eq!(reloaded.count(), 1);
+/// # };
+/// ```
+pub struct Signal {
+    lock: Slock<()>,
+}
+
+impl Slock<()> {
+    /// Use this lock as a [`Signal`].
+    pub fn signal(&self) -> Signal {
+        Signal { lock: self.clone() }
+    }
+}
+
+impl Signal {
+    /// Create a signal that hasn't been notified yet.
+    pub fn new() -> Self {
+        Slock::new(()).signal()
+    }
+
+    /// The lock behind the signal, for hooking.
+    pub fn lock(&self) -> &Slock<()> {
+        &self.lock
+    }
+
+    /// How many times the signal has been notified.
+    pub fn count(&self) -> u64 {
+        *self.lock.lock.changes.borrow()
+    }
+
+    /// Notify the signal, waking every task waiting on it.
+    pub async fn notify(&self) {
+        self.lock.set(|v| v).await;
+    }
+
+    /// Wait for the signal to be notified after this is called, returning the new count.
+    pub async fn wait(&self) -> u64 {
+        self.wait_count(self.count() + 1).await
+    }
+
+    /// Wait until the signal has been notified `n` times in total, returning the count.
+    ///
+    /// Returns straight away if it already has been.
+    pub async fn wait_count(&self, n: u64) -> u64 {
+        let mut receiver = self.lock.lock.changes.subscribe();
+        loop {
+            let count = *receiver.borrow_and_update();
+            if count >= n {
+                return count;
+            }
+            // The sender lives as long as the lock, which `self` holds
+            receiver.changed().await.ok();
+        }
+    }
+}
+
+impl Default for Signal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for Signal {
+    fn clone(&self) -> Self {
+        Self {
+            lock: self.lock.clone(),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::{
+    marker::PhantomData,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use tokio::sync::Mutex;
+
+use crate::{
+    event::{self, SlockEvent},
+    Error,
+};
+
+/// A value small enough for a [`SmallSlock`] to hold in one atomic word.
+///
+/// Implemented for the primitive integers, floats, `bool` and `char`.
+/// `from_bits` is only ever given bits that came from `to_bits`.
+pub trait SmallValue: Copy {
+    /// Pack the value into 64 bits.
+    fn to_bits(self) -> u64;
+    /// Unpack a value packed by [`SmallValue::to_bits`].
+    fn from_bits(bits: u64) -> Self;
+}
+
+macro_rules! small_int {
+    ($($int:ty),*) => {$(
+        impl SmallValue for $int {
+            fn to_bits(self) -> u64 {
+                self as u64
+            }
+
+            fn from_bits(bits: u64) -> Self {
+                bits as $int
+            }
+        }
+    )*};
+}
+
+small_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl SmallValue for bool {
+    fn to_bits(self) -> u64 {
+        self as u64
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        bits != 0
+    }
+}
+
+impl SmallValue for char {
+    fn to_bits(self) -> u64 {
+        self as u64
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        char::from_u32(bits as u32).expect("bits came from a char")
+    }
+}
+
+impl SmallValue for f32 {
+    fn to_bits(self) -> u64 {
+        f32::to_bits(self) as u64
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        f32::from_bits(bits as u32)
+    }
+}
+
+impl SmallValue for f64 {
+    fn to_bits(self) -> u64 {
+        f64::to_bits(self)
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        f64::from_bits(bits)
+    }
+}
+
+/// A lock for counters, flags and other tiny `Copy` values, held in an atomic instead of a `RwLock`.
+///
+/// Has the same basic API as [`Slock`](crate::Slock), but reads never wait for a lock.
+/// Sets still wait for each other, so each setter runs once on the latest value.
+/// There are no hooks, validators or metrics, and closures are never timed out.
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let hits = SmallSlock::new(0u64);
+/// hits.set(|v| v + 1).await;
+/// assert_eq!(hits.get().await, 1);
+/// # };
+/// ```
+pub struct SmallSlock<T> {
+    bits: Arc<AtomicU64>,
+    /// Held by sets and replaces, so none of them overwrites a value another has just set.
+    writer: Arc<Mutex<()>>,
+    value: PhantomData<T>,
+}
+
+impl<T: SmallValue> SmallSlock<T> {
+    /// Create a new lock with a given initial value.
+    pub fn new(value: T) -> Self {
+        Self {
+            bits: Arc::new(AtomicU64::new(value.to_bits())),
+            writer: Arc::new(Mutex::new(())),
+            value: PhantomData,
+        }
+    }
+
+    /// Returns a copy of the value.
+    pub async fn get(&self) -> T {
+        T::from_bits(self.bits.load(Ordering::Acquire))
+    }
+
+    /// Like [`Slock::map`](crate::Slock::map), returning [`Error::ClosurePanicked`] if `mapper` panics.
+    pub async fn map<F, U>(&self, mapper: F) -> Result<U, Error>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        let value = self.get().await;
+        catch_unwind(AssertUnwindSafe(|| mapper(&value))).map_err(|_| Error::ClosurePanicked)
+    }
+
+    /// Change the value.
+    ///
+    /// If `setter` panics, the value is left as it was and the failure is only reported to the
+    /// [`set_event_logger`](crate::set_event_logger) logger. Use [`SmallSlock::try_set`] to handle it instead,
+    /// or [`SmallSlock::replace`] to get the old value back.
+    pub async fn set<F>(&self, setter: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        if let Err(error) = self.try_set(setter).await {
+            event::emit(SlockEvent::WriteDropped {
+                lock: None,
+                label: None,
+                error: &error,
+            });
+        }
+    }
+
+    /// Like [`SmallSlock::set`], but returns [`Error::ClosurePanicked`] if `setter` panics.
+    pub async fn try_set<F>(&self, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        let _writer = self.writer.lock().await;
+        let value = T::from_bits(self.bits.load(Ordering::Acquire));
+        let new =
+            catch_unwind(AssertUnwindSafe(|| setter(value))).map_err(|_| Error::ClosurePanicked)?;
+        self.bits.store(new.to_bits(), Ordering::Release);
+        Ok(())
+    }
+
+    /// Replace the value, returning the old one.
+    pub async fn replace(&self, value: T) -> T {
+        let _writer = self.writer.lock().await;
+        T::from_bits(self.bits.swap(value.to_bits(), Ordering::AcqRel))
+    }
+}
+
+impl<T> Clone for SmallSlock<T> {
+    fn clone(&self) -> Self {
+        Self {
+            bits: self.bits.clone(),
+            writer: self.writer.clone(),
+            value: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::{ops::Deref, sync::OnceLock};
+
+use crate::Slock;
+
+/// A Slock that can be declared in a `static`, created on first use.
+///
+/// Usually declared with [`static_slock!`](crate::static_slock).
+/// ```rust
+/// # use slock::*;
+/// static COUNTER: StaticSlock<i32> = StaticSlock::new(|| 0);
+///
+/// # async {
+/// COUNTER.set(|v| v + 1).await;
+/// # };
+/// ```
+pub struct StaticSlock<T> {
+    lock: OnceLock<Slock<T>>,
+    init: fn() -> T,
+}
+
+impl<T> StaticSlock<T> {
+    /// Declare a lock whose initial value will come from `init`.
+    pub const fn new(init: fn() -> T) -> Self {
+        Self {
+            lock: OnceLock::new(),
+            init,
+        }
+    }
+}
+
+impl<T> Deref for StaticSlock<T> {
+    type Target = Slock<T>;
+
+    fn deref(&self) -> &Slock<T> {
+        self.lock.get_or_init(|| Slock::new((self.init)()))
+    }
+}
+
+impl<T: 'static> Slock<T> {
+    /// Promote a lock to live for the rest of the program, without a `static` declaration.
+    ///
+    /// The handle is never dropped, so the value is never freed.
+    /// ```rust
+    /// # use slock::*;
+    /// let config: &'static Slock<bool> = Slock::new(false).leak();
+    ///
+    /// std::thread::spawn(move || {
+    ///     // Usable anywhere a `'static` reference is needed
+    ///     let _ = config.name();
+    /// });
+    /// ```
+    pub fn leak(self) -> &'static Slock<T> {
+        Box::leak(Box::new(self))
+    }
+}
+
+/// Declare global Slocks without any lazy initialization boilerplate.
+/// ```rust
+/// # use slock::*;
+/// # #[derive(Clone)]
+/// # struct Config { verbose: bool }
+/// static_slock! {
+///     static CONFIG: Config = Config { verbose: false };
+///     pub static REQUESTS: u64 = 0;
+/// }
+///
+/// # async {
+/// REQUESTS.set(|v| v + 1).await;
+/// let verbose = CONFIG.map(|c| c.verbose).await.unwrap();
+/// # };
+/// ```
+#[macro_export]
+macro_rules! static_slock {
+    ($($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr;)*) => {
+        $(
+            $(#[$attr])*
+            $vis static $name: $crate::StaticSlock<$t> = $crate::StaticSlock::new(|| $init);
+        )*
+    };
+}
@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+
+use futures::{stream, Stream};
+use tokio::sync::broadcast;
+
+use crate::Slock;
+
+/// How many unread values each [`Slock::changes`] stream buffers, unless set with
+/// [`SlockBuilder::change_buffer`](crate::SlockBuilder::change_buffer).
+const DEFAULT_CAPACITY: usize = 16;
+
+/// Marks values that a [`Slock::changes_marked`] stream fell too far behind to receive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lagged(
+    /// How many values were skipped.
+    pub u64,
+);
+
+/// Broadcasts every new value to change streams.
+pub(crate) struct Feed<T> {
+    sender: broadcast::Sender<(u64, T)>,
+    snapshot: fn(&T) -> T,
+}
+
+impl<T: Clone> Feed<T> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Feed {
+            sender: broadcast::channel(capacity.max(1)).0,
+            snapshot: T::clone,
+        }
+    }
+}
+
+impl<T> Feed<T> {
+    /// Sends a copy of the value, unless nothing is listening.
+    pub(crate) fn send(&self, version: u64, value: &T) {
+        if self.sender.receiver_count() > 0 {
+            self.sender.send((version, (self.snapshot)(value))).ok();
+        }
+    }
+}
+
+impl<T: Clone> Slock<T> {
+    /// A stream of every value the lock takes from now on, along with its version.
+    ///
+    /// Each stream buffers a bounded number of unread values. A stream that falls further behind
+    /// skips ahead to the oldest buffered value. See [`Slock::changes_marked`] to find out when that happens.
+    /// ```rust
+    /// # use slock::*;
+    /// # use futures::StreamExt;
+    /// # async {
+    /// let lock = Slock::new(0i32);
+    /// let mut changes = Box::pin(lock.changes().await);
+    ///
+    /// lock.set(|v| v + 1).await;
+    /// assert_eq!(changes.next().await, Some((1, 1)));
+    /// # };
+    /// ```
+    pub async fn changes(&self) -> impl Stream<Item = (u64, T)> {
+        stream::unfold(self.subscribe().await, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(change) => return Some((change, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// Like [`Slock::changes`], but yields [`Lagged`] in place of values the stream fell too far behind to receive.
+    pub async fn changes_marked(&self) -> impl Stream<Item = Result<(u64, T), Lagged>> {
+        stream::unfold(self.subscribe().await, |mut receiver| async move {
+            match receiver.recv().await {
+                Ok(change) => Some((Ok(change), receiver)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    Some((Err(Lagged(skipped)), receiver))
+                }
+                Err(broadcast::error::RecvError::Closed) => None,
+            }
+        })
+    }
+
+    async fn subscribe(&self) -> broadcast::Receiver<(u64, T)> {
+        if let Some(feed) = self.read().await.feed.as_ref() {
+            return feed.sender.subscribe();
+        }
+        let mut data = self.write().await;
+        data.feed
+            .get_or_insert_with(|| Feed::new(DEFAULT_CAPACITY))
+            .sender
+            .subscribe()
+    }
+}
+
+/// ## Queues
+impl<T> Slock<VecDeque<T>> {
+    /// A stream that takes items off the front of the queue as they arrive.
+    ///
+    /// Waits for the next set whenever the queue is empty. Several streams can drain the same queue,
+    /// with each item going to exactly one of them. The stream never ends on its own.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::collections::VecDeque;
+    /// # use futures::StreamExt;
+    /// # async {
+    /// let jobs = Slock::new(VecDeque::new());
+    /// let mut worker = Box::pin(jobs.drain_stream());
+    ///
+    /// jobs.set(|mut q| { q.push_back("resize image"); q }).await;
+    /// assert_eq!(worker.next().await, Some("resize image"));
+    /// # };
+    /// ```
+    pub fn drain_stream(&self) -> impl Stream<Item = T> {
+        stream::unfold(self.clone(), |lock| async move {
+            loop {
+                let mut changes = lock.lock.changes.subscribe();
+                changes.borrow_and_update();
+                if let Some(item) = lock.pop_front().await {
+                    return Some((item, lock));
+                }
+                changes.changed().await.ok();
+            }
+        })
+    }
+
+    /// Removes the front item, only counting as a set if there was one.
+    async fn pop_front(&self) -> Option<T> {
+        // Waiting for room in the write queue never fails
+        let _queued = self.enqueue(false).await;
+        let mut data = self.write_as(None).await;
+        let item = data.value.pop_front()?;
+        self.publish(&mut data).await;
+        Some(item)
+    }
+}
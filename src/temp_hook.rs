@@ -0,0 +1,105 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use crate::{Hook, Slock};
+
+/// Hooks installed for the duration of a [`Slock::with_hook`] call, or for the crate's own use.
+///
+/// Kept outside the lock's data, so they can be removed without acquiring it when the call is cancelled,
+/// and so they never take the slot of the lock's own [`Slock::hook`].
+pub(crate) struct TempHooks<T> {
+    next_id: AtomicU64,
+    hooks: Mutex<Vec<(u64, Hook<T>)>>,
+}
+
+impl<T> Default for TempHooks<T> {
+    fn default() -> Self {
+        Self {
+            next_id: AtomicU64::new(0),
+            hooks: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl<T> TempHooks<T> {
+    fn add(&self, hook: Hook<T>) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.hooks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((id, hook));
+        id
+    }
+
+    fn remove(&self, id: u64) {
+        self.hooks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|(hook_id, _)| *hook_id != id);
+    }
+
+    pub(crate) fn call(&self, value: &T) {
+        let mut hooks = self.hooks.lock().unwrap_or_else(|e| e.into_inner());
+        for (_, hook) in hooks.iter_mut() {
+            hook(value);
+        }
+    }
+}
+
+/// Removes a temporary hook when dropped, including when its future is cancelled.
+struct Installed<'a, T> {
+    hooks: &'a TempHooks<T>,
+    id: u64,
+}
+
+impl<T> Drop for Installed<'_, T> {
+    fn drop(&mut self) {
+        self.hooks.remove(self.id);
+    }
+}
+
+impl<T> Slock<T> {
+    /// Run `hook` after every set for as long as the lock lives, alongside the lock's own [`Slock::hook`].
+    #[cfg(feature = "rt")]
+    pub(crate) fn add_hook(&self, hook: Hook<T>) {
+        self.lock.temp_hooks.add(hook);
+    }
+
+    /// Run `hook` after every set made while `future` runs, then remove it.
+    ///
+    /// The hook is removed even if the returned future is dropped before finishing.
+    /// It runs alongside the lock's own [`Slock::hook`], without replacing it,
+    /// but must not call `with_hook` on the same lock.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let score = Slock::new(0i32);
+    /// let total = score
+    ///     .with_hook(|v| println!("score is now {v}"), async {
+    ///         score.set(|v| v + 10).await;
+    ///         score.get().await
+    ///     })
+    ///     .await;
+    ///
+    /// // No longer printed
+    /// score.set(|v| v + 1).await;
+    /// # };
+    /// ```
+    pub async fn with_hook<F, Fut>(&self, hook: F, future: Fut) -> Fut::Output
+    where
+        F: FnMut(&T) + Send + Sync + 'static,
+        Fut: Future,
+    {
+        let hooks = &self.lock.temp_hooks;
+        let _installed = Installed {
+            hooks,
+            id: hooks.add(Box::new(hook)),
+        };
+        future.await
+    }
+}
@@ -0,0 +1,199 @@
+//! Test doubles for code that depends on Slocks.
+//!
+//! ```rust
+//! use slock::{testing::MockSlock, Error, Slock};
+//! use std::time::Duration;
+//!
+//! # async {
+//! let lock = MockSlock::from(Slock::builder().timeout(Duration::from_millis(10)).build(0i32));
+//!
+//! // Reads can be scripted...
+//! lock.queue_read(42);
+//! assert_eq!(lock.map(|v| *v).await.unwrap(), 42);
+//!
+//! // ...and slow acquisitions can push operations past the timeout
+//! lock.delay_acquisitions(Duration::from_millis(50));
+//! assert!(matches!(lock.try_set(|v| v + 1).await, Err(Error::Timeout)));
+//! lock.assert_set_called(1);
+//! # };
+//! ```
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::Stream;
+
+use crate::{
+    event::{self, SlockEvent},
+    time::{sleep, timeout},
+    Error, Slock,
+};
+
+/// A Slock that records how it is used and can be scripted to misbehave.
+///
+/// Clones share the same value and the same script.
+pub struct MockSlock<T> {
+    lock: Slock<T>,
+    script: Arc<Mutex<Script<T>>>,
+}
+
+struct Script<T> {
+    sets: usize,
+    reads: VecDeque<T>,
+    delay: Duration,
+}
+
+impl<T> MockSlock<T> {
+    /// Create a new mock with a given initial value.
+    pub fn new(value: T) -> Self {
+        Slock::new(value).into()
+    }
+
+    /// The real lock backing this mock.
+    pub fn lock(&self) -> &Slock<T> {
+        &self.lock
+    }
+
+    /// Queue a value to be seen by the next read instead of the lock's real value.
+    ///
+    /// Queued values are used up in order, one per read.
+    pub fn queue_read(&self, value: T) {
+        self.script.lock().unwrap().reads.push_back(value);
+    }
+
+    /// Wait `delay` before every following acquisition.
+    ///
+    /// Delays longer than the lock's timeout make operations fail with [`Error::Timeout`].
+    pub fn delay_acquisitions(&self, delay: Duration) {
+        self.script.lock().unwrap().delay = delay;
+    }
+
+    /// The number of times the lock has been set, including sets that failed.
+    pub fn set_calls(&self) -> usize {
+        self.script.lock().unwrap().sets
+    }
+
+    /// Panics unless the lock has been set exactly `n` times.
+    #[track_caller]
+    pub fn assert_set_called(&self, n: usize) {
+        let sets = self.set_calls();
+        assert_eq!(sets, n, "expected {n} calls to set, found {sets}");
+    }
+
+    /// Like [`Slock::map`], but sees a queued read value if there is one.
+    pub async fn map<F, U>(&self, mapper: F) -> Result<U, Error>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        self.acquire().await?;
+        let queued = self.script.lock().unwrap().reads.pop_front();
+        match queued {
+            Some(value) => Ok(mapper(&value)),
+            None => self.lock.map(mapper).await,
+        }
+    }
+
+    /// Like [`Slock::set`], recording the call.
+    pub async fn set<F>(&self, setter: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        if let Err(error) = self.try_set(setter).await {
+            event::emit(SlockEvent::WriteDropped {
+                lock: self.lock.name(),
+                label: None,
+                error: &error,
+            });
+        }
+    }
+
+    /// Like [`Slock::try_set`], recording the call.
+    pub async fn try_set<F>(&self, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        self.script.lock().unwrap().sets += 1;
+        self.acquire().await?;
+        self.lock.try_set(setter).await
+    }
+
+    /// Conditionally replaces the value, recording the call as a set.
+    ///
+    /// `updater` sees the lock's real value, and returns the new value, if any, along with a result.
+    /// Returns the result and whether a new value was stored.
+    pub async fn update<F, R>(&self, updater: F) -> Result<(R, bool), Error>
+    where
+        F: FnOnce(&T) -> (Option<T>, R),
+    {
+        self.script.lock().unwrap().sets += 1;
+        self.acquire().await?;
+        self.lock.update(updater).await
+    }
+
+    /// Like [`Slock::hook`], on the real lock.
+    ///
+    /// Queued read values aren't stored in the lock, so they never reach the hook.
+    pub async fn hook<F>(&self, hook: F)
+    where
+        F: FnMut(&T) + Send + Sync + 'static,
+    {
+        self.lock.hook(hook).await;
+    }
+
+    /// Waits out the scripted delay, bounded by the lock's timeout.
+    async fn acquire(&self) -> Result<(), Error> {
+        let delay = self.script.lock().unwrap().delay;
+        if delay.is_zero() {
+            return Ok(());
+        }
+        let result = timeout(self.lock.lock.timeout, sleep(delay)).await;
+        if result.is_err() {
+            self.lock.lock.counters.timed_out();
+        }
+        Ok(result?)
+    }
+}
+
+impl<T: Clone> MockSlock<T> {
+    /// Like [`Slock::get_clone`], but sees a queued read value if there is one.
+    pub async fn get_clone(&self) -> Result<T, Error> {
+        self.map(T::clone).await
+    }
+
+    /// Like [`Slock::changes`], for values stored in the real lock.
+    pub async fn changes(&self) -> impl Stream<Item = (u64, T)> {
+        self.lock.changes().await
+    }
+}
+
+impl<T: Copy> MockSlock<T> {
+    /// Like [`Slock::get`], but sees a queued read value if there is one.
+    pub async fn get(&self) -> Result<T, Error> {
+        self.map(|v| *v).await
+    }
+}
+
+impl<T> From<Slock<T>> for MockSlock<T> {
+    fn from(lock: Slock<T>) -> Self {
+        Self {
+            lock,
+            script: Arc::new(Mutex::new(Script {
+                sets: 0,
+                reads: VecDeque::new(),
+                delay: Duration::ZERO,
+            })),
+        }
+    }
+}
+
+impl<T> Clone for MockSlock<T> {
+    fn clone(&self) -> Self {
+        Self {
+            lock: self.lock.clone(),
+            script: self.script.clone(),
+        }
+    }
+}
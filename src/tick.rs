@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::{time, Slock};
+
+impl<T: Send + Sync + 'static> Slock<T> {
+    /// Call `hook` with the latest value at most once every `tick`, and only if the lock was set since the last call.
+    ///
+    /// Unlike [`Slock::hook`], intermediate values set between ticks are skipped,
+    /// so a fast producer can't flood a slow consumer.
+    /// The task stops on its own once every handle to the lock has been dropped.
+    /// ```rust
+    /// # use slock::*;
+    /// # use std::time::Duration;
+    /// # async {
+    /// let cursor = Slock::new((0i32, 0i32));
+    /// cursor.hook_per_tick(Duration::from_millis(16), |(x, y)| {
+    ///     println!("redraw at {x}, {y}");
+    /// });
+    /// # };
+    /// ```
+    pub fn hook_per_tick<F>(&self, tick: Duration, mut hook: F) -> JoinHandle<()>
+    where
+        F: FnMut(&T) + Send + 'static,
+    {
+        let weak = self.downgrade();
+        let mut changes = self.lock.changes.subscribe();
+        tokio::spawn(async move {
+            let mut interval = time::interval(tick);
+            loop {
+                interval.tick().await;
+                let Some(lock) = Slock::upgrade(&weak) else {
+                    break;
+                };
+                if changes.has_changed().unwrap_or(false) {
+                    changes.borrow_and_update();
+                    lock.map(|v| hook(v)).await.ok();
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,122 @@
+//! The timer machinery used to contain lock operations and drive timed features.
+//!
+//! Without the `time` feature (e.g. on `wasm32-unknown-unknown`) there is no timer,
+//! so operations simply run to completion and [`Error::Timeout`](crate::Error::Timeout) is never returned.
+//! The same goes for operations run where tokio's timer can't be used: outside of a tokio runtime, such as from another executor,
+//! or in a runtime built without `enable_time`.
+//! Timed features like leases and rate limiters still wait there, on a thread per wait.
+//!
+//! A runtime without `enable_time` can only be detected by unwinding, so builds with `panic = "abort"`
+//! must run Slocks in a runtime with the timer enabled, if they run them in a tokio runtime at all.
+
+#[cfg(feature = "time")]
+pub use tokio::time::{error::Elapsed, Instant};
+
+#[cfg(feature = "time")]
+use std::{future::Future, time::Duration};
+
+/// Whether tokio's timer can be used from here.
+///
+/// The timer panics outside of a runtime, and in a runtime without the time driver.
+/// Only the first can be checked for directly.
+#[cfg(feature = "time")]
+fn has_timer() -> bool {
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        return false;
+    };
+    driver_enabled(handle.id())
+}
+
+/// Finds the time driver by trying to make a timer, once per runtime on each thread.
+///
+/// The runtime's panic message is printed when that fails.
+#[cfg(all(feature = "time", panic = "unwind"))]
+fn driver_enabled(id: tokio::runtime::Id) -> bool {
+    use std::cell::Cell;
+
+    thread_local! {
+        static CHECKED: Cell<Option<(tokio::runtime::Id, bool)>> = const { Cell::new(None) };
+    }
+    CHECKED.with(|checked| match checked.get() {
+        Some((checked_id, enabled)) if checked_id == id => enabled,
+        _ => {
+            let enabled =
+                std::panic::catch_unwind(|| drop(tokio::time::sleep(Duration::ZERO))).is_ok();
+            checked.set(Some((id, enabled)));
+            enabled
+        }
+    })
+}
+
+/// Without unwinding, trying to make a timer would abort the process, so the runtime is assumed to have one.
+#[cfg(all(feature = "time", not(panic = "unwind")))]
+fn driver_enabled(_: tokio::runtime::Id) -> bool {
+    true
+}
+
+#[cfg(feature = "time")]
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+    if !has_timer() {
+        return Ok(future.await);
+    }
+    tokio::time::timeout(duration, future).await
+}
+
+#[cfg(feature = "time")]
+pub async fn sleep(duration: Duration) {
+    sleep_until(Instant::now() + duration).await
+}
+
+#[cfg(feature = "time")]
+pub async fn sleep_until(deadline: Instant) {
+    if has_timer() {
+        return tokio::time::sleep_until(deadline).await;
+    }
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+        sender.send(()).ok();
+    });
+    receiver.await.ok();
+}
+
+/// Ticks at most once every `period`, starting straight away.
+///
+/// A late tick pushes back the ones after it, rather than bursting to catch up.
+#[cfg(all(feature = "rt", feature = "time"))]
+pub fn interval(period: Duration) -> Interval {
+    Interval {
+        next: Instant::now(),
+        period,
+    }
+}
+
+#[cfg(all(feature = "rt", feature = "time"))]
+pub struct Interval {
+    next: Instant,
+    period: Duration,
+}
+
+#[cfg(all(feature = "rt", feature = "time"))]
+impl Interval {
+    pub async fn tick(&mut self) {
+        sleep_until(self.next).await;
+        self.next = Instant::now() + self.period;
+    }
+}
+
+#[cfg(not(feature = "time"))]
+pub use fallback::{timeout, Elapsed};
+
+#[cfg(not(feature = "time"))]
+mod fallback {
+    use std::{future::Future, time::Duration};
+
+    /// Never constructed without the `time` feature.
+    #[derive(Debug)]
+    pub struct Elapsed(());
+
+    pub async fn timeout<F: Future>(_: Duration, future: F) -> Result<F::Output, Elapsed> {
+        Ok(future.await)
+    }
+}
@@ -0,0 +1,117 @@
+use futures::future::BoxFuture;
+
+use crate::{Error, Slock};
+
+/// A lock that can take part in a [`ReadToken`], whatever type it holds.
+trait Participant: Send + Sync {
+    fn id(&self) -> usize;
+
+    /// Read locks the lock, returning its version and the guard holding it.
+    fn read_version(&self) -> BoxFuture<'_, (u64, Box<dyn Send + '_>)>;
+}
+
+impl<T: Send + Sync> Participant for Slock<T> {
+    fn id(&self) -> usize {
+        Slock::id(self)
+    }
+
+    fn read_version(&self) -> BoxFuture<'_, (u64, Box<dyn Send + '_>)> {
+        Box::pin(async move {
+            let data = self.read().await;
+            (data.version, Box::new(data) as Box<dyn Send>)
+        })
+    }
+}
+
+/// The versions of one or more locks at the moment they were read, created by [`Slock::read_token`].
+///
+/// Pass it to [`Slock::set_with_token`] to only write if none of them have been written to since,
+/// guarding read-compute-write flows against write skew.
+pub struct ReadToken {
+    reads: Vec<(u64, Box<dyn Participant>)>,
+}
+
+impl ReadToken {
+    /// Combine two tokens, so a write depends on the locks read for both.
+    pub fn and(mut self, other: ReadToken) -> ReadToken {
+        self.reads.extend(other.reads);
+        self
+    }
+}
+
+impl<T: Send + Sync + 'static> Slock<T> {
+    /// Capture the lock's current version.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let on_call = Slock::new(vec!["alice", "bob"]);
+    /// let leave = Slock::new(Vec::<&str>::new());
+    ///
+    /// // Only go on leave if someone else is still on call, and nobody else went on leave meanwhile
+    /// let token = on_call.read_token().await.and(leave.read_token().await);
+    /// if on_call.map(|v| v.len()).await.unwrap() > 1 {
+    ///     leave
+    ///         .set_with_token(&token, |mut v| { v.push("alice"); v })
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// # };
+    /// ```
+    pub async fn read_token(&self) -> ReadToken {
+        let version = self.read().await.version;
+        ReadToken {
+            reads: vec![(version, Box::new(self.clone()))],
+        }
+    }
+
+    /// Like [`Slock::try_set`], but returns [`Error::Conflict`] without writing
+    /// if any lock in `token` has been written to since the token was taken.
+    ///
+    /// Every other lock in the token is read locked until the write is done, so none can change part way through.
+    pub async fn set_with_token<F>(&self, token: &ReadToken, setter: F) -> Result<(), Error>
+    where
+        F: FnOnce(T) -> T,
+    {
+        let mut others: Vec<_> = token
+            .reads
+            .iter()
+            .filter(|(_, lock)| lock.id() != self.id())
+            .collect();
+        others.sort_by_key(|(_, lock)| lock.id());
+
+        // Acquire every lock in order of id, like every other multi-lock operation
+        let _queued = self.enqueue(true).await?;
+        let mut data = None;
+        let mut guards = Vec::new();
+        let mut last: Option<(usize, u64)> = None;
+        let mut conflict = false;
+        for (version, lock) in others {
+            if data.is_none() && lock.id() > self.id() {
+                data = Some(self.try_write_as(None).await?);
+            }
+            let current = match last {
+                Some((id, current)) if id == lock.id() => current,
+                _ => {
+                    let (current, guard) = lock.read_version().await;
+                    guards.push(guard);
+                    last = Some((lock.id(), current));
+                    current
+                }
+            };
+            conflict |= current != *version;
+        }
+        let mut data = match data {
+            Some(data) => data,
+            None => self.try_write_as(None).await?,
+        };
+
+        conflict |= token
+            .reads
+            .iter()
+            .any(|(version, lock)| lock.id() == self.id() && *version != data.version);
+        if conflict {
+            return Err(Error::Conflict);
+        }
+        self.apply(&mut data, setter).await
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashSet;
+
+use futures::future::{self, BoxFuture, FutureExt};
+
+use crate::{Error, Slock};
+
+/// A node in a tree of Slocks.
+///
+/// Every node owns a value lock and a list of child nodes.
+/// Parents can aggregate over their whole subtree and observe changes made anywhere beneath them.
+///
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let scene = SlockTree::new(1i32);
+/// let child = scene.add_child(2).await;
+/// child.add_child(3).await;
+///
+/// let total = scene.fold(0, |sum, v| sum + v).await.unwrap();
+/// assert_eq!(total, 6);
+/// # };
+/// ```
+pub struct SlockTree<T> {
+    value: Slock<T>,
+    children: Slock<Vec<SlockTree<T>>>,
+}
+
+impl<T> SlockTree<T> {
+    /// Create a new root node with a given initial value.
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Slock::new(value),
+            children: Slock::new(Vec::new()),
+        }
+    }
+
+    /// The lock holding this node's own value.
+    pub fn value(&self) -> &Slock<T> {
+        &self.value
+    }
+
+    /// Create a new node and register it as a child of this one.
+    pub async fn add_child(&self, value: T) -> SlockTree<T> {
+        let child = SlockTree::new(value);
+        self.attach(child.clone()).await;
+        child
+    }
+
+    /// Register an existing node (and its subtree) as a child of this one.
+    pub async fn attach(&self, child: SlockTree<T>) {
+        self.children.push(child).await;
+    }
+
+    /// Returns a snapshot of this node's direct children.
+    pub async fn children(&self) -> Vec<SlockTree<T>> {
+        self.children.peek(|children| children.clone()).await
+    }
+
+    /// Returns this node followed by all of its descendants, depth first.
+    ///
+    /// A node attached in more than one place, or to its own subtree, is only visited once.
+    pub async fn nodes(&self) -> Vec<SlockTree<T>> {
+        let mut nodes = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![self.clone()];
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node.children.id()) {
+                continue;
+            }
+            let mut children = node.children().await;
+            children.reverse();
+            stack.extend(children);
+            nodes.push(node);
+        }
+        nodes
+    }
+
+    /// Aggregate every value in the subtree, depth first.
+    ///
+    /// Each value is read under its own lock, so no two locks are ever held at once.
+    pub async fn fold<A, F>(&self, init: A, mut folder: F) -> Result<A, Error>
+    where
+        F: FnMut(A, &T) -> A,
+    {
+        let folder = &mut folder;
+        let mut acc = init;
+        for node in self.nodes().await {
+            acc = node.value.map(|v| folder(acc, v)).await?;
+        }
+        Ok(acc)
+    }
+
+    /// Wait until any value in the subtree is set, or a child is attached anywhere in it.
+    pub async fn changed(&self)
+    where
+        T: Send,
+    {
+        let nodes = self.nodes().await;
+        let changes: Vec<BoxFuture<'_, ()>> = nodes
+            .iter()
+            .flat_map(|node| {
+                [
+                    node.value.changed().boxed(),
+                    node.children.changed().boxed(),
+                ]
+            })
+            .collect();
+        future::select_all(changes).await;
+    }
+}
+
+impl<T> Clone for SlockTree<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            children: self.children.clone(),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+//! Common patterns built on Slocks, so the fiddly parts only have to be gotten right once.
+
+#[cfg(feature = "time")]
+mod circuit_breaker;
+#[cfg(feature = "time")]
+mod rate_limiter;
+
+#[cfg(feature = "time")]
+pub use circuit_breaker::{BreakerState, CircuitBreaker};
+#[cfg(feature = "time")]
+pub use rate_limiter::RateLimiter;
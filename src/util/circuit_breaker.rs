@@ -0,0 +1,199 @@
+use std::{future::Future, time::Duration};
+
+use crate::{time::Instant, Error, Slock, SlockMachine};
+
+/// Whether a [`CircuitBreaker`] is letting calls through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BreakerState {
+    /// Calls go through, and failures are counted.
+    Closed,
+    /// Too many calls failed, so calls are refused until the breaker's reset time has passed.
+    Open,
+    /// A single trial call is deciding whether to close the breaker again or keep it open.
+    HalfOpen,
+}
+
+/// Stops calling a failing service for a while, so it gets a chance to recover.
+///
+/// After `threshold` failures in a row the breaker opens, and calls fail with [`Error::CircuitOpen`] without being made.
+/// Once `reset_after` has passed, one trial call is let through: if it succeeds the breaker closes, otherwise it opens again.
+/// The state is a [`SlockMachine`], so it can be waited on and hooked like any other lock.
+/// ```rust
+/// # use slock::{util::{BreakerState, CircuitBreaker}, Error};
+/// # use std::time::Duration;
+/// # async fn fetch_prices() -> Result<Vec<u32>, std::io::Error> { Ok(vec![]) }
+/// # async {
+/// let breaker = CircuitBreaker::new(5, Duration::from_secs(30));
+/// breaker
+///     .on_transition(|from, to| println!("price feed breaker: {from:?} -> {to:?}"))
+///     .await;
+///
+/// match breaker.call(fetch_prices).await {
+///     Ok(Ok(prices)) => println!("got {} prices", prices.len()),
+///     Ok(Err(e)) => println!("price feed failed: {e}"),
+///     Err(Error::CircuitOpen) => println!("price feed is down, using cached prices"),
+///     Err(e) => println!("breaker failed: {e}"),
+/// }
+/// # };
+/// ```
+pub struct CircuitBreaker {
+    state: SlockMachine<BreakerState>,
+    /// Failures in a row while closed.
+    failures: Slock<u32>,
+    /// When the breaker last opened, or last let a trial call through.
+    opened: Slock<Instant>,
+    threshold: u32,
+    reset_after: Duration,
+}
+
+impl CircuitBreaker {
+    /// Create a closed breaker, which opens after `threshold` failures in a row and tries again after `reset_after`.
+    ///
+    /// A `threshold` of zero is treated as one.
+    pub fn new(threshold: u32, reset_after: Duration) -> Self {
+        Self {
+            state: SlockMachine::new(BreakerState::Closed),
+            failures: Slock::new(0),
+            opened: Slock::new(Instant::now()),
+            threshold: threshold.max(1),
+            reset_after,
+        }
+    }
+
+    /// The lock holding the breaker's state, for waiting on or hooking.
+    pub fn lock(&self) -> &Slock<BreakerState> {
+        self.state.lock()
+    }
+
+    /// The breaker's current state.
+    pub async fn state(&self) -> BreakerState {
+        self.state.lock().get().await
+    }
+
+    /// How many calls have failed in a row since the breaker last closed.
+    pub async fn failures(&self) -> u32 {
+        self.failures.get().await
+    }
+
+    /// Call `listener` with the previous and new state whenever the breaker changes state.
+    pub async fn on_transition<F>(&self, mut listener: F)
+    where
+        F: FnMut(BreakerState, BreakerState) + Send + Sync + 'static,
+    {
+        self.state
+            .on_transition(move |from, to| listener(*from, *to))
+            .await;
+    }
+
+    /// Make the call with `f`, unless the breaker is open.
+    ///
+    /// Returns [`Error::CircuitOpen`] without calling `f` if the breaker is open, or a trial call is already under way.
+    /// Otherwise returns the call's result, after counting it towards opening or closing the breaker.
+    ///
+    /// A trial call that is dropped before finishing lets another call try once `reset_after` has passed again.
+    pub async fn call<F, Fut, T, E>(&self, f: F) -> Result<Result<T, E>, Error>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let trial = self.admit().await?;
+        let result = f().await;
+        // The call was made, so its result is returned even if the breaker couldn't record it
+        self.record(trial, result.is_ok()).await.ok();
+        Ok(result)
+    }
+
+    /// Counts a finished call towards opening or closing the breaker.
+    async fn record(&self, trial: bool, succeeded: bool) -> Result<(), Error> {
+        match (succeeded, trial) {
+            (true, true) => self.close().await,
+            (true, false) => {
+                self.failures
+                    .update(|failures| (Some(0).filter(|_| *failures != 0), ()))
+                    .await?;
+                Ok(())
+            }
+            (false, true) => self.open(BreakerState::HalfOpen).await,
+            (false, false) => {
+                let (failures, _) = self
+                    .failures
+                    .update(|failures| {
+                        let failures = failures.saturating_add(1);
+                        (Some(failures), failures)
+                    })
+                    .await?;
+                // Only the failure that reaches the threshold opens the breaker
+                if failures == self.threshold {
+                    self.open(BreakerState::Closed).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Decides whether a call may go ahead, returning whether it is a trial call.
+    async fn admit(&self) -> Result<bool, Error> {
+        let state = self.state().await;
+        if state == BreakerState::Closed {
+            return Ok(false);
+        }
+        // Claiming the trial moves the reset time along, so only one caller gets it
+        let now = Instant::now();
+        let reset_after = self.reset_after;
+        let (claimed, _) = self
+            .opened
+            .update(|opened| {
+                let due = now.saturating_duration_since(*opened) >= reset_after;
+                (Some(now).filter(|_| due), due)
+            })
+            .await?;
+        if !claimed {
+            return Err(Error::CircuitOpen);
+        }
+        if state == BreakerState::Open {
+            // Another caller may have moved the breaker on already
+            self.state
+                .transition(|s| *s == BreakerState::Open, BreakerState::HalfOpen)
+                .await
+                .ok();
+        }
+        Ok(true)
+    }
+
+    /// Opens the breaker, if it is still in state `from`.
+    async fn open(&self, from: BreakerState) -> Result<(), Error> {
+        self.opened.set(|_| Instant::now()).await;
+        match self
+            .state
+            .transition(|s| *s == from, BreakerState::Open)
+            .await
+        {
+            Ok(_) | Err(Error::InvalidTransition) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Closes the breaker after a successful trial call.
+    async fn close(&self) -> Result<(), Error> {
+        self.failures.set(|_| 0).await;
+        let closed = self
+            .state
+            .transition(|s| *s == BreakerState::HalfOpen, BreakerState::Closed);
+        match closed.await {
+            Ok(_) | Err(Error::InvalidTransition) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Clone for CircuitBreaker {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            failures: self.failures.clone(),
+            opened: self.opened.clone(),
+            threshold: self.threshold,
+            reset_after: self.reset_after,
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use crate::{
+    time::{sleep, Instant},
+    Slock,
+};
+
+/// The tokens left in a [`RateLimiter`], and when they were last topped up.
+#[derive(Clone, Copy)]
+struct Bucket {
+    tokens: u32,
+    refilled: Instant,
+}
+
+/// A token bucket, held in a Slock so every clone draws from the same tokens.
+///
+/// The bucket starts full and gains a token every `interval`, up to its capacity.
+/// Tokens are topped up whenever the bucket is used, so no task is needed to refill it.
+/// ```rust
+/// # use slock::util::RateLimiter;
+/// # use std::time::Duration;
+/// # async {
+/// // Bursts of up to 10 requests, and 5 a second after that
+/// let limiter = RateLimiter::new(10, Duration::from_millis(200));
+///
+/// limiter.acquire(1).await;
+/// if !limiter.try_acquire(3).await {
+///     println!("too many requests");
+/// }
+/// # };
+/// ```
+pub struct RateLimiter {
+    bucket: Slock<Bucket>,
+    capacity: u32,
+    interval: Duration,
+}
+
+impl RateLimiter {
+    /// Create a full bucket of `capacity` tokens, which gains a token every `interval`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn new(capacity: u32, interval: Duration) -> Self {
+        assert!(
+            !interval.is_zero(),
+            "a rate limiter's interval can't be zero"
+        );
+        Self {
+            bucket: Slock::new(Bucket {
+                tokens: capacity,
+                refilled: Instant::now(),
+            }),
+            capacity,
+            interval,
+        }
+    }
+
+    /// The most tokens the bucket can hold.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// The bucket as of `now`, with the tokens gained since it was last topped up.
+    fn refill(&self, mut bucket: Bucket, now: Instant) -> Bucket {
+        let missing = self.capacity.saturating_sub(bucket.tokens);
+        let elapsed = now.saturating_duration_since(bucket.refilled);
+        let gained = (elapsed.as_nanos() / self.interval.as_nanos()).min(missing as u128) as u32;
+        if gained == missing {
+            // Tokens don't build up past a full bucket
+            bucket.tokens = self.capacity;
+            bucket.refilled = now;
+        } else {
+            bucket.tokens += gained;
+            bucket.refilled += self.interval * gained;
+        }
+        bucket
+    }
+
+    /// Takes `n` tokens if there are enough, otherwise returns how long until there will be.
+    async fn take(&self, n: u32) -> Result<(), Duration> {
+        let taken = self.bucket.update(|bucket| {
+            let now = Instant::now();
+            let mut bucket = self.refill(*bucket, now);
+            if bucket.tokens >= n {
+                bucket.tokens -= n;
+                return (Some(bucket), Ok(()));
+            }
+            let wait = self.interval * (n - bucket.tokens) - (now - bucket.refilled);
+            (None, Err(wait))
+        });
+        // The updater can't time out or panic, but if it did, try again shortly
+        taken.await.map_or(Err(self.interval), |(taken, _)| taken)
+    }
+
+    /// Take `n` tokens, waiting for them if there aren't enough.
+    ///
+    /// Waiting tasks aren't queued, so a task taking a few tokens may overtake one waiting for many.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than the bucket's capacity, since it would wait forever.
+    pub async fn acquire(&self, n: u32) {
+        assert!(
+            n <= self.capacity,
+            "can't acquire {n} tokens from a rate limiter with a capacity of {}",
+            self.capacity
+        );
+        while let Err(wait) = self.take(n).await {
+            sleep(wait).await;
+        }
+    }
+
+    /// Take `n` tokens if there are enough right now, returning whether they were taken.
+    pub async fn try_acquire(&self, n: u32) -> bool {
+        self.take(n).await.is_ok()
+    }
+
+    /// How many tokens are in the bucket right now.
+    pub async fn available(&self) -> u32 {
+        let bucket = self.bucket.map(|bucket| *bucket).await;
+        bucket.map_or(0, |bucket| self.refill(bucket, Instant::now()).tokens)
+    }
+}
+
+impl Clone for RateLimiter {
+    fn clone(&self) -> Self {
+        Self {
+            bucket: self.bucket.clone(),
+            capacity: self.capacity,
+            interval: self.interval,
+        }
+    }
+}
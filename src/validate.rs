@@ -0,0 +1,91 @@
+use std::{error::Error, fmt};
+
+use crate::Slock;
+
+/// Returned when a lock's validator rejects a new value.
+#[derive(Debug)]
+pub struct ValidationError(Box<dyn Error + Send + Sync>);
+
+impl ValidationError {
+    pub(crate) fn new(error: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        ValidationError(error.into())
+    }
+
+    /// The error returned by the validator.
+    pub fn into_inner(self) -> Box<dyn Error + Send + Sync> {
+        self.0
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "value rejected by validator: {}", self.0)
+    }
+}
+
+impl Error for ValidationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.0)
+    }
+}
+
+type Check<T> = Box<dyn Fn(&T) -> Result<(), ValidationError> + Send + Sync>;
+
+/// Checks every value a lock is about to store.
+pub(crate) struct Validator<T> {
+    check: Check<T>,
+    /// Keeps a copy of the old value, since setters consume it.
+    backup: fn(&T) -> T,
+}
+
+impl<T: Clone> Validator<T> {
+    pub(crate) fn new<F, E>(validator: F) -> Self
+    where
+        F: Fn(&T) -> Result<(), E> + Send + Sync + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Validator {
+            check: Box::new(move |v: &T| validator(v).map_err(ValidationError::new)),
+            backup: T::clone,
+        }
+    }
+}
+
+impl<T> Validator<T> {
+    pub(crate) fn check(&self, value: &T) -> Result<(), ValidationError> {
+        (self.check)(value)
+    }
+
+    pub(crate) fn backup(&self, value: &T) -> T {
+        (self.backup)(value)
+    }
+}
+
+impl<T: Clone> Slock<T> {
+    /// Create a new Slock whose values must always pass `validator`.
+    ///
+    /// Every write runs the validator on the prospective value, and rejected values are never stored.
+    /// Use [`Slock::try_set`] to find out why a write was rejected.
+    /// Helpers such as `fetch_max` or `saturating_add` treat a rejected value as no change.
+    ///
+    /// Since setters take the old value by ownership, validated locks clone it before every write
+    /// so it can be restored.
+    /// ```rust
+    /// # use slock::*;
+    /// # async {
+    /// let health = Slock::new_validated(100i32, |v| {
+    ///     if *v >= 0 { Ok(()) } else { Err("health can't be negative") }
+    /// });
+    ///
+    /// assert!(health.try_set(|v| v - 150).await.is_err());
+    /// assert_eq!(health.get().await, 100);
+    /// # };
+    /// ```
+    pub fn new_validated<F, E>(value: T, validator: F) -> Self
+    where
+        F: Fn(&T) -> Result<(), E> + Send + Sync + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Slock::builder().validator(validator).build(value)
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{Slock, SlockGroup};
+
+/// A vector whose elements each have their own lock, built on a [`SlockGroup`].
+///
+/// Like a [`SlockMap`](crate::SlockMap), the outer lock is only written when elements are added or removed,
+/// so setting one element never waits on writes to another.
+/// The group's operations, like [`SlockGroup::broadcast_set`], work on the elements through [`SlockVec::lock`].
+/// ```rust
+/// # use slock::*;
+/// # async {
+/// let players = SlockVec::new();
+/// players.push(100i32).await;
+/// players.push(80).await;
+///
+/// players.get(1).await.unwrap().set(|hp| hp - 30).await;
+/// assert_eq!(players.get(1).await.unwrap().get().await, 50);
+///
+/// players.lock().broadcast_set(|hp| hp + 5).await;
+/// # };
+/// ```
+pub struct SlockVec<T> {
+    elements: SlockGroup<T>,
+}
+
+impl<T> SlockVec<T> {
+    /// Create a new, empty vector.
+    pub fn new() -> Self {
+        Self {
+            elements: SlockGroup::new_group(),
+        }
+    }
+
+    /// The group holding the element locks.
+    pub fn lock(&self) -> &SlockGroup<T> {
+        &self.elements
+    }
+
+    /// Get the lock of the element at `index`, if there is one.
+    pub async fn get(&self, index: usize) -> Option<Slock<T>> {
+        self.elements
+            .peek(|elements| elements.get(index).cloned())
+            .await
+    }
+
+    /// Append a value in its own lock, returning its index.
+    pub async fn push(&self, value: T) -> usize {
+        let element = Slock::new(value);
+        let mut index = 0;
+        self.elements
+            .set(|mut elements| {
+                index = elements.len();
+                elements.push(element);
+                elements
+            })
+            .await;
+        index
+    }
+
+    /// Remove the element at `index`, replacing it with the last element. Returns the removed lock.
+    ///
+    /// Unlike [`Vec::swap_remove`], an out of bounds index returns `None` instead of panicking,
+    /// since another task may have shortened the vector.
+    pub async fn swap_remove(&self, index: usize) -> Option<Slock<T>> {
+        let mut removed = None;
+        self.elements
+            .set(|mut elements| {
+                if index < elements.len() {
+                    removed = Some(elements.swap_remove(index));
+                }
+                elements
+            })
+            .await;
+        removed
+    }
+
+    /// The number of elements.
+    pub async fn len(&self) -> usize {
+        self.elements.peek(|elements| elements.len()).await
+    }
+
+    /// Returns true if there are no elements.
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
+
+impl<T> From<SlockGroup<T>> for SlockVec<T> {
+    /// Index into an existing group's members.
+    fn from(group: SlockGroup<T>) -> Self {
+        Self { elements: group }
+    }
+}
+
+impl<T> Default for SlockVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for SlockVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            elements: self.elements.clone(),
+        }
+    }
+}
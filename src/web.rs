@@ -0,0 +1,66 @@
+use std::{convert::Infallible, ops::Deref};
+
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::{request::Parts, StatusCode},
+    routing::{get, MethodRouter},
+    Json,
+};
+use serde::Serialize;
+
+use crate::Slock;
+
+/// An axum extractor for a `Slock` held in the application's state.
+///
+/// The state must provide the lock through `FromRef`, for example with `#[derive(FromRef)]`.
+/// ```rust
+/// # use slock::*;
+/// use axum::{routing::post, Router};
+///
+/// async fn hit(SlockState(hits): SlockState<u64>) {
+///     hits.set(|v| v + 1).await;
+/// }
+///
+/// let hits = Slock::new(0u64);
+/// let app: Router = Router::new()
+///     .route("/hit", post(hit))
+///     .route("/hits", json_route(hits.clone()))
+///     .with_state(hits);
+/// ```
+pub struct SlockState<T>(pub Slock<T>);
+
+impl<S, T> FromRequestParts<S> for SlockState<T>
+where
+    Slock<T>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Ok(SlockState(Slock::from_ref(state)))
+    }
+}
+
+impl<T> Deref for SlockState<T> {
+    type Target = Slock<T>;
+
+    fn deref(&self) -> &Slock<T> {
+        &self.0
+    }
+}
+
+/// A `GET` route responding with the lock's current value as JSON.
+///
+/// The value is serialized under the read lock, so it is never cloned.
+pub fn json_route<S, T>(lock: Slock<T>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+    T: Serialize + Send + Sync + 'static,
+{
+    get(move || async move {
+        match lock.map(|v| serde_json::to_value(v)).await {
+            Ok(Ok(value)) => Ok(Json(value)),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    })
+}
@@ -0,0 +1,18 @@
+#![cfg(feature = "bytes")]
+
+use bytes::Bytes;
+use slock::*;
+
+/// Reads should share the buffer rather than copy it.
+#[tokio::test]
+async fn zero_copy() {
+    let frame = Slock::new(Bytes::new());
+    frame.append(Bytes::from_static(b"hello")).await;
+    frame.append(Bytes::from_static(b" world")).await;
+
+    let whole = frame.get_bytes().await;
+    assert_eq!(whole, "hello world");
+    let word = frame.slice(6..).await;
+    assert_eq!(word, "world");
+    assert_eq!(word.as_ptr(), whole[6..].as_ptr());
+}
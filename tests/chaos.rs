@@ -0,0 +1,51 @@
+#![cfg(feature = "chaos")]
+
+use slock::*;
+use std::time::Duration;
+
+/// A lock that always times out should never apply a write.
+#[tokio::test]
+async fn spurious_timeouts() {
+    let lock = Slock::builder()
+        .chaos(Chaos::seeded(7).timeouts(1.0))
+        .build(0i32);
+    assert!(matches!(lock.try_set(|v| v + 1).await, Err(Error::Timeout)));
+    assert!(matches!(lock.map(|v| *v).await, Err(Error::Timeout)));
+    assert_eq!(lock.metrics().timeouts, 2);
+    assert_eq!(lock.metrics().version, 0);
+}
+
+/// Delayed and reordered writes should all still land.
+#[tokio::test]
+async fn delayed_writes() {
+    let lock = Slock::builder()
+        .chaos(
+            Chaos::seeded(7)
+                .delays(Duration::from_millis(2))
+                .reorder_writes(),
+        )
+        .build(0i32);
+    futures::future::join_all((0..10).map(|_| lock.set(|v| v + 1))).await;
+    assert_eq!(lock.get().await, 10);
+}
+
+/// The crate's own helpers shouldn't be timed out along with user closures.
+#[tokio::test]
+async fn helpers_ignore_spurious_timeouts() {
+    let flag = Slock::builder()
+        .chaos(Chaos::seeded(7).timeouts(1.0))
+        .build(false);
+    assert!(flag.toggle().await);
+
+    let count = Slock::builder()
+        .chaos(Chaos::seeded(7).timeouts(1.0))
+        .build(250u8);
+    assert_eq!(count.saturating_add(10).await, 255);
+
+    let map: SlockMap<u32, &str> = Slock::builder()
+        .chaos(Chaos::seeded(7).timeouts(1.0))
+        .build(Default::default());
+    assert!(map.from_key(1).await.is_none());
+    assert!(map.entries().await.is_empty());
+    assert_eq!(map.metrics().timeouts, 0);
+}
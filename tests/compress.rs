@@ -0,0 +1,23 @@
+#![cfg(feature = "compress")]
+
+use slock::*;
+
+/// Values should survive compression, and reads should see every write.
+#[tokio::test]
+async fn round_trip() {
+    let lock = Slock::new_compressed(vec![7u64; 10_000], Codec::Zstd).unwrap();
+    assert!(lock.compressed_len().await < 1000);
+    assert_eq!(lock.map(|v| v.len()).await.unwrap(), 10_000);
+
+    let other = lock.clone();
+    other
+        .set(|mut v| {
+            v[0] = 1;
+            v
+        })
+        .await
+        .unwrap();
+    assert_eq!(lock.map(|v| v[0]).await.unwrap(), 1);
+    assert_eq!(lock.get_clone().await.unwrap()[1], 7);
+    assert_eq!(lock.lock().metrics().version, 1);
+}
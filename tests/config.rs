@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use slock::*;
+
+/// Global defaults should apply to new locks only, and be overridable per lock.
+#[tokio::test]
+async fn global_defaults() {
+    let before = Slock::new(0);
+    slock::configure(
+        GlobalConfig::default()
+            .metrics_enabled(false)
+            .default_timeout(Duration::from_secs(5)),
+    );
+    assert!(!slock::config().metrics_enabled);
+
+    let after = Slock::new(0);
+    let overridden = Slock::builder().metrics(true).build(0);
+    for lock in [&before, &after, &overridden] {
+        lock.set(|v| v + 1).await;
+    }
+    assert_eq!(before.metrics().acquisitions, 1);
+    assert_eq!(after.metrics().acquisitions, 0);
+    assert_eq!(overridden.metrics().acquisitions, 1);
+}
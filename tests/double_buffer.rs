@@ -0,0 +1,18 @@
+#![cfg(feature = "double-buffer")]
+
+use slock::*;
+
+/// Reads should keep seeing the front buffer until the back buffer is swapped in.
+#[tokio::test]
+async fn swap_buffers() {
+    let buffered = Slock::new_buffered(1);
+    let held = buffered.front();
+
+    buffered.set(|v| v + 1).await;
+    assert_eq!(buffered.read(|v| *v), 1);
+
+    buffered.swap_buffers().await;
+    assert_eq!(buffered.read(|v| *v), 2);
+    assert_eq!(*held, 1);
+    assert_eq!(buffered.back().get().await, 2);
+}
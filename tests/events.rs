@@ -0,0 +1,50 @@
+#![cfg(feature = "time")]
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use slock::*;
+
+/// Failures that `set` (directly or through a session or lease), hooks and read leases would otherwise swallow should reach the event logger.
+#[tokio::test]
+async fn event_logger() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let log = events.clone();
+    slock::set_event_logger(move |event| log.lock().unwrap().push(event.to_string()));
+
+    let health = Slock::builder()
+        .named("health")
+        .validator(|v: &i32| if *v >= 0 { Ok(()) } else { Err("negative") })
+        .build(10);
+    health.set(|v| v - 20).await;
+
+    health.hook(|_| panic!("hook bug")).await;
+    health.set(|v| v - 1).await;
+
+    assert_eq!(health.get().await, 9);
+
+    let lease = health.read_for(Duration::from_millis(10)).await;
+    let slow = lease.within(tokio::time::sleep(Duration::from_millis(30)));
+    assert!(matches!(slow.await, Err(Error::Timeout)));
+    assert!(lease.is_expired());
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(health.metrics().lease_violations, 1);
+    drop(lease);
+    assert_eq!(health.metrics().lease_violations, 1);
+
+    health.session().set(|v| v - 20).await;
+    let lease = health.lease(Duration::from_secs(1)).await;
+    lease.set(|v| v - 20).await;
+    drop(lease);
+    assert_eq!(health.get().await, 9);
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 5);
+    assert!(events[0].starts_with("write dropped on lock `health`"));
+    assert!(events[1].starts_with("hook failed on lock `health`"));
+    assert!(events[2].starts_with("read overran on lock `health`"));
+    assert!(events[3].starts_with("write dropped on lock `health`"));
+    assert!(events[4].starts_with("write dropped on lock `health`"));
+}
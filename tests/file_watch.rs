@@ -0,0 +1,24 @@
+#![cfg(feature = "file-watch")]
+
+use slock::*;
+use std::time::Duration;
+
+/// Writing to a watched file should update the lock.
+#[tokio::test]
+async fn reload_on_change() {
+    let path = std::env::temp_dir().join(format!("slock-watch-{}.txt", std::process::id()));
+    std::fs::write(&path, "1").unwrap();
+
+    let lock = Slock::from_file_watch(&path, |text| text.trim().parse::<i32>()).unwrap();
+    assert_eq!(lock.get().await, 1);
+
+    let reloaded = tokio::time::timeout(Duration::from_secs(5), async {
+        tokio::join!(lock.wait_until(|v| *v == 2), async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            std::fs::write(&path, "2").unwrap();
+        })
+    })
+    .await;
+    std::fs::remove_file(&path).ok();
+    assert!(reloaded.is_ok());
+}
@@ -1,6 +1,7 @@
 use slock::*;
 
 #[tokio::test]
+#[allow(static_mut_refs)]
 async fn basic_hooks() {
     // SAFETY: Required to increment the static counter
     unsafe {
@@ -13,3 +14,425 @@ async fn basic_hooks() {
         assert_eq!(COUNT, 3);
     }
 }
+
+/// Changes anywhere in a subtree should be seen by its root.
+#[tokio::test]
+async fn tree_bubbling() {
+    let root = SlockTree::new(0i32);
+    let leaf = root.add_child(1).await.add_child(2).await;
+    tokio::join!(root.changed(), leaf.value().set(|v| v + 1));
+    assert_eq!(root.fold(0, |sum, v| sum + v).await.unwrap(), 4);
+
+    // Waiting from a spawned task, on a tree with a cycle in it
+    leaf.attach(root.clone()).await;
+    assert_eq!(root.nodes().await.len(), 3);
+    let waiter = tokio::spawn({
+        let root = root.clone();
+        async move { root.changed().await }
+    });
+    tokio::task::yield_now().await;
+    leaf.value().set(|v| v + 1).await;
+    waiter.await.unwrap();
+}
+
+/// Waiting on a flag should resolve once another task raises it.
+#[tokio::test]
+async fn flag_wait() {
+    let flag = Slock::new(false);
+    let raiser = flag.clone();
+    tokio::join!(flag.wait_until_true(), async move {
+        tokio::task::yield_now().await;
+        raiser.set_true().await;
+    });
+    assert!(!flag.toggle().await);
+}
+
+/// Flags should start from the environment and accept overrides.
+#[tokio::test]
+async fn env_flags() {
+    use slock::flags::FlagSlock;
+
+    std::env::set_var("SLOCK_TEST_FLAG", "On");
+    let flag = FlagSlock::from_env("SLOCK_TEST_FLAG");
+    assert!(flag.is_enabled().await);
+
+    flag.set_false().await;
+    assert!(!flag.is_enabled().await);
+    flag.reload().await;
+    assert!(flag.is_enabled().await);
+
+    assert!(
+        !FlagSlock::from_env("SLOCK_TEST_FLAG_UNSET")
+            .is_enabled()
+            .await
+    );
+}
+
+/// Events should only reach subscribers of their own type.
+#[tokio::test]
+async fn event_bus() {
+    struct Joined(i32);
+    struct Left;
+
+    let bus = SlockBus::new();
+    let total = Slock::new(0i32);
+    let counter = total.clone();
+    bus.subscribe(move |event: std::sync::Arc<Joined>| {
+        let counter = counter.clone();
+        async move { counter.set(|v| v + event.0).await }
+    })
+    .await;
+
+    assert_eq!(bus.publish(Joined(3)).await, 1);
+    assert_eq!(bus.publish(Joined(4)).await, 1);
+    assert_eq!(bus.publish(Left).await, 0);
+    assert_eq!(total.get().await, 7);
+
+    // Concurrent first subscribers to an event type are all kept
+    let subscribe =
+        |bus: SlockBus| async move { bus.subscribe(|_: std::sync::Arc<Left>| async {}).await };
+    tokio::join!(subscribe(bus.clone()), subscribe(bus.clone()));
+    assert_eq!(bus.publish(Left).await, 2);
+}
+
+/// Setters within one window should share a single write.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn coalesced_writes() {
+    let lock = Slock::new(Vec::new());
+    let window = std::time::Duration::from_millis(20);
+    tokio::join!(
+        lock.set_coalesced(window, |mut v| {
+            v.push(1);
+            v
+        }),
+        lock.set_coalesced(window, |mut v| {
+            v.push(2);
+            v
+        }),
+        lock.set_coalesced(window, |mut v| {
+            v.push(3);
+            v
+        }),
+    );
+    assert_eq!(lock.get_clone().await, vec![1, 2, 3]);
+    assert_eq!(lock.metrics().version, 1);
+}
+
+/// Machines should refuse transitions from the wrong state, and report the rest.
+#[tokio::test]
+async fn state_machine() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum Link {
+        Down,
+        Connecting,
+        Up,
+    }
+
+    let link = SlockMachine::new(Link::Down);
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = seen.clone();
+    link.on_transition(move |from, to| log.lock().unwrap().push((from.clone(), to.clone())))
+        .await;
+    // A panicking listener doesn't stop the others, or later ones
+    link.on_transition(|_, _| panic!("listener bug")).await;
+
+    let connect = link.transition(|s| *s == Link::Down, Link::Connecting);
+    assert_eq!(connect.await.unwrap(), Link::Down);
+    assert!(matches!(
+        link.transition(|s| *s == Link::Down, Link::Up).await,
+        Err(Error::InvalidTransition)
+    ));
+    link.transition(|s| *s == Link::Connecting, Link::Up)
+        .await
+        .unwrap();
+
+    assert_eq!(link.state().await, Link::Up);
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![(Link::Down, Link::Connecting), (Link::Connecting, Link::Up)]
+    );
+
+    let log = seen.clone();
+    link.on_transition(move |_, to| log.lock().unwrap().push((to.clone(), to.clone())))
+        .await;
+    link.transition(|s| *s == Link::Up, Link::Down)
+        .await
+        .unwrap();
+    assert_eq!(
+        seen.lock().unwrap()[2..],
+        [(Link::Up, Link::Down), (Link::Down, Link::Down)]
+    );
+}
+
+/// Other writes should wait for a lease to be released.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn leases() {
+    let lock = Slock::new(Vec::new());
+    let lease = lock.lease(std::time::Duration::from_secs(5)).await;
+    let outside = lock.clone();
+    tokio::join!(
+        outside.set(|mut v| {
+            v.push("outside");
+            v
+        }),
+        async {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            lease
+                .set(|mut v| {
+                    v.push("lease");
+                    v
+                })
+                .await;
+            assert!(lease.is_active());
+            drop(lease);
+        }
+    );
+    assert_eq!(lock.get_clone().await, vec!["lease", "outside"]);
+
+    // Expired leases stop blocking writes
+    let _expired = lock.lease(std::time::Duration::from_millis(10)).await;
+    lock.set(|_| Vec::new()).await;
+    assert!(lock.get_clone().await.is_empty());
+}
+
+/// A batch should run the hook once, with the final value.
+#[tokio::test]
+async fn batched_writes() {
+    let lock = Slock::new(1i32);
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = seen.clone();
+    lock.hook(move |v| log.lock().unwrap().push(*v)).await;
+    lock.batch(|b| {
+        b.set(|v| v + 1).set(|v| v * 10);
+        b.set(|v| v - 5);
+    })
+    .await;
+    assert_eq!(*seen.lock().unwrap(), vec![15]);
+}
+
+/// Per-tick hooks should only see the latest value of a burst.
+#[cfg(all(feature = "rt", feature = "time"))]
+#[tokio::test]
+async fn tick_hooks() {
+    let lock = Slock::new(0i32);
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = seen.clone();
+    lock.hook_per_tick(std::time::Duration::from_millis(20), move |v| {
+        log.lock().unwrap().push(*v)
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    for _ in 0..100 {
+        lock.set(|v| v + 1).await;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![100]);
+
+    // Tasks on the same lock don't keep each other running
+    let tick = std::time::Duration::from_millis(5);
+    let tasks = [
+        lock.hook_per_tick(tick, |_| {}),
+        lock.hook_per_tick(tick, |_| {}),
+    ];
+    drop(lock);
+    for task in tasks {
+        tokio::time::timeout(std::time::Duration::from_millis(100), task)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
+
+/// Projected hooks should only run when the projection changes.
+#[tokio::test]
+async fn projected_hooks() {
+    let lock = Slock::new((0i32, "a"));
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = seen.clone();
+    lock.hook_on(|v| v.1, move |name| log.lock().unwrap().push(*name))
+        .await;
+    lock.set(|(n, name)| (n + 1, name)).await;
+    lock.set(|(n, _)| (n, "b")).await;
+    lock.set(|(n, name)| (n + 1, name)).await;
+    lock.set(|(n, _)| (n, "a")).await;
+    assert_eq!(*seen.lock().unwrap(), vec!["b", "a"]);
+}
+
+/// Replacing a map should report exactly which keys changed.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn map_replacement() {
+    let routes = SlockMap::new_map();
+    routes.insert(1, |_| "a").await;
+    routes.insert(2, |_| "b").await;
+    routes.insert(3, |_| "c").await;
+    let kept = routes.from_key(2).await.unwrap();
+
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = seen.clone();
+    routes.on_change(move |change| log.lock().unwrap().push(change));
+
+    let new_map = std::collections::HashMap::from([(1, "a"), (2, "B"), (4, "d")]);
+    let mut changes = routes.replace_all(new_map).await;
+    changes.sort_by_key(|change| format!("{change:?}"));
+    assert_eq!(
+        changes,
+        vec![
+            MapChange::Added(4),
+            MapChange::Changed(2),
+            MapChange::Removed(3)
+        ]
+    );
+    assert_eq!(seen.lock().unwrap().len(), 3);
+    assert_eq!(kept.get().await, "B");
+    assert!(routes.from_key(3).await.is_none());
+
+    // Waiting on a busy entry shouldn't keep the map locked
+    let lease = kept.lease(std::time::Duration::from_secs(1)).await;
+    let new_map = std::collections::HashMap::from([(2, "b")]);
+    let (changes, found) = tokio::join!(routes.replace_all(new_map), async {
+        let lookup = routes.from_key(2);
+        let found = tokio::time::timeout(std::time::Duration::from_millis(100), lookup).await;
+        drop(lease);
+        found
+    });
+    assert!(found.unwrap().is_some());
+    assert_eq!(changes.len(), 3);
+}
+
+/// Map subscribers should hear about keys coming and going, but not value changes.
+#[tokio::test]
+async fn map_membership() {
+    let map = SlockMap::new_map();
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = seen.clone();
+    map.on_insert(move |k, v: &i32| log.lock().unwrap().push(format!("+{k}={v}")));
+    let log = seen.clone();
+    map.on_remove(move |k| log.lock().unwrap().push(format!("-{k}")));
+
+    map.insert("a", |_| 1).await;
+    map.insert("a", |v| v.unwrap() + 1).await;
+    map.at("b").await;
+    assert!(map.remove("a").await.is_some());
+    assert!(map.remove("a").await.is_none());
+    assert_eq!(map.prune_unreferenced().await, 1);
+    assert_eq!(*seen.lock().unwrap(), vec!["+a=1", "+b=0", "-a", "-b"]);
+
+    // A panicking subscriber doesn't break later changes to the map
+    map.on_insert(|k, _| assert_ne!(k, "c"));
+    let panicking = map.clone();
+    assert!(
+        tokio::spawn(async move { panicking.insert("c", |_| 3).await })
+            .await
+            .is_err()
+    );
+    map.insert("d", |_| 4).await;
+    assert!(map.remove("d").await.is_some());
+    assert_eq!(seen.lock().unwrap()[4..], ["+c=3", "+d=4", "-d"]);
+}
+
+/// Change streams should see every value, and mark the ones they fall behind on.
+#[tokio::test]
+async fn change_streams() {
+    use futures::StreamExt;
+
+    let lock = Slock::builder().change_buffer(2).build(0i32);
+    let mut changes = Box::pin(lock.changes().await);
+    let mut marked = Box::pin(lock.changes_marked().await);
+
+    lock.set(|v| v + 1).await;
+    assert_eq!(changes.next().await, Some((1, 1)));
+    for _ in 0..3 {
+        lock.set(|v| v + 1).await;
+    }
+    assert_eq!(changes.next().await, Some((3, 3)));
+    assert_eq!(changes.next().await, Some((4, 4)));
+
+    assert_eq!(marked.next().await, Some(Err(Lagged(2))));
+    assert_eq!(marked.next().await, Some(Ok((3, 3))));
+}
+
+/// Hooks run by a dedicated task should see every value in order, without holding up sets.
+#[cfg(feature = "rt")]
+#[tokio::test]
+async fn dedicated_hooks() {
+    let lock = Slock::builder()
+        .hook_strategy(HookStrategy::Dedicated)
+        .build(0i32);
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = seen.clone();
+    lock.hook(move |v| {
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        log.lock().unwrap().push(*v)
+    })
+    .await;
+    for _ in 0..5 {
+        lock.set(|v| v + 1).await;
+    }
+    assert!(seen.lock().unwrap().len() < 5);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2, 3, 4, 5]);
+}
+
+/// Spawned hooks should run for every set, on copies of the values.
+#[cfg(feature = "rt")]
+#[tokio::test]
+async fn spawned_hooks() {
+    let lock = Slock::builder()
+        .hook_strategy(HookStrategy::Spawned)
+        .build(0i32);
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = seen.clone();
+    lock.hook(move |v| log.lock().unwrap().push(*v)).await;
+    for _ in 0..5 {
+        lock.set(|v| v + 1).await;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let mut seen = seen.lock().unwrap().clone();
+    seen.sort();
+    assert_eq!(seen, vec![1, 2, 3, 4, 5]);
+}
+
+/// Temporary hooks should only see sets made while their future runs, even if it's cancelled.
+#[tokio::test]
+async fn temporary_hooks() {
+    let lock = Slock::new(0i32);
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = seen.clone();
+    let doubled = lock
+        .with_hook(move |v| log.lock().unwrap().push(*v), async {
+            lock.set(|v| v + 1).await;
+            lock.get().await * 2
+        })
+        .await;
+    assert_eq!(doubled, 2);
+    lock.set(|v| v + 1).await;
+    assert_eq!(*seen.lock().unwrap(), vec![1]);
+
+    let log = seen.clone();
+    let cancelled = lock.with_hook(
+        move |v| log.lock().unwrap().push(*v),
+        std::future::pending::<()>(),
+    );
+    let _ = tokio::time::timeout(std::time::Duration::from_millis(5), cancelled).await;
+    lock.set(|v| v + 1).await;
+    assert_eq!(*seen.lock().unwrap(), vec![1]);
+}
+
+/// Awaiting a lock, or its `changed` future, should resolve on the next set.
+#[tokio::test]
+async fn changed_futures() {
+    let lock = Slock::new(0i32);
+    let changed = lock.changed();
+    lock.set(|v| v + 1).await;
+    changed.await;
+
+    let setter = lock.clone();
+    tokio::spawn(async move { setter.set(|v| v + 1).await });
+    tokio::select! {
+        _ = &lock => {}
+        _ = tokio::time::sleep(std::time::Duration::from_secs(1)) => panic!("set was missed"),
+    }
+    assert_eq!(lock.get().await, 2);
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "ipc")]
+
+use slock::ipc::IpcSlock;
+
+/// Separate mappings of the same file should share one value.
+#[tokio::test]
+async fn shared_value() {
+    let path = std::env::temp_dir().join(format!("slock-ipc-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let first = IpcSlock::open(&path, 5u64).await.unwrap();
+    let second = IpcSlock::open(&path, 100u64).await.unwrap();
+    assert_eq!(second.get().await.unwrap(), 5);
+
+    futures::future::join_all((0..10).map(|i| {
+        let lock = if i % 2 == 0 { &first } else { &second };
+        lock.set(|v| v + 1)
+    }))
+    .await;
+    assert_eq!(first.get().await.unwrap(), 15);
+    assert_eq!(second.version_count(), 10);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// The pid of a process that has already exited.
+fn dead_pid() -> u32 {
+    let mut child = std::process::Command::new("true").spawn().unwrap();
+    child.wait().unwrap();
+    child.id()
+}
+
+/// Overwrite one of the lock's header words, as another process would.
+fn poke(path: &std::path::Path, offset: u64, word: u32) {
+    use std::io::{Seek, SeekFrom, Write};
+
+    let mut file = std::fs::OpenOptions::new().write(true).open(path).unwrap();
+    file.seek(SeekFrom::Start(offset)).unwrap();
+    file.write_all(&word.to_ne_bytes()).unwrap();
+}
+
+/// A process dying while it holds the lock, or while it initializes the file, shouldn't wedge everyone else.
+#[cfg(unix)]
+#[tokio::test]
+async fn dead_holders() {
+    let path = std::env::temp_dir().join(format!("slock-ipc-dead-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let lock = IpcSlock::open(&path, 1u64)
+        .await
+        .unwrap()
+        .with_timeout(std::time::Duration::from_millis(100));
+
+    poke(&path, 0, dead_pid());
+    lock.set(|v| v + 1).await;
+    assert_eq!(lock.get().await.unwrap(), 2);
+
+    poke(&path, 4, dead_pid());
+    let reopened = IpcSlock::open(&path, 7u64).await.unwrap();
+    assert_eq!(reopened.get().await.unwrap(), 7);
+
+    std::fs::remove_file(&path).unwrap();
+}
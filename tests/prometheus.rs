@@ -0,0 +1,28 @@
+#![cfg(feature = "prometheus")]
+
+use prometheus::core::Collector;
+use slock::*;
+
+/// Locks sharing a name should share a series, without the counters ever going backwards.
+#[tokio::test]
+async fn shared_names() {
+    let collector = SlockCollector::new();
+    let busy = Slock::new(0);
+    let idle = Slock::new(0);
+    collector.register("jobs", &busy);
+    collector.register("jobs", &idle);
+
+    for _ in 0..3 {
+        busy.set(|v| v + 1).await;
+    }
+    idle.set(|v| v + 1).await;
+
+    for _ in 0..2 {
+        let families = collector.collect();
+        let acquisitions = families
+            .iter()
+            .find(|family| family.name() == "slock_acquisitions_total")
+            .unwrap();
+        assert_eq!(acquisitions.get_metric()[0].get_counter().get_value(), 3.0);
+    }
+}
@@ -0,0 +1,20 @@
+#![cfg(feature = "redis")]
+
+use slock::*;
+
+/// Writes from one handle should be seen by another connected to the same key.
+#[tokio::test]
+#[ignore = "needs a Redis server at REDIS_URL"]
+async fn shared_between_instances() {
+    let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".into());
+    let client = redis::Client::open(url).unwrap();
+    let key = format!("slock-test-{}", std::process::id());
+
+    let first = DistSlock::connect(&client, &key, 1i64).await.unwrap();
+    let second = DistSlock::connect(&client, &key, 100i64).await.unwrap();
+    assert_eq!(second.get_clone().await.unwrap(), 1);
+
+    first.set(|v| v + 1).await.unwrap();
+    second.set(|v| v * 10).await.unwrap();
+    assert_eq!(first.get_clone().await.unwrap(), 20);
+}
@@ -1,6 +1,22 @@
 use lazy_static::lazy_static;
 use slock::*;
 use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+/// Run a future on its own thread, for holding a lock while the test carries on.
+fn in_thread<F>(future: F) -> std::thread::JoinHandle<F::Output>
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    std::thread::spawn(move || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(future)
+    })
+}
 
 #[tokio::test]
 async fn synchronous() {
@@ -36,6 +52,9 @@ async fn reference_counting() {
     lock_1.set(|_| 1).await;
     assert_eq!(lock_1.get().await, 1);
     assert_eq!(lock_2.get().await, 1);
+    assert_eq!(lock_1.ref_count(), 2);
+    std::mem::drop(lock_2);
+    assert!(!lock_1.has_other_refs());
 }
 
 #[tokio::test]
@@ -55,6 +74,7 @@ async fn mapping() {
 
     assert_eq!(name, "Bob");
     assert_eq!(age, 32);
+    assert_eq!(lock.map_cloned(|v| &v.name).await.unwrap(), "Bob");
 }
 
 /// A slock containing a vector should be able to asynchronously push.
@@ -109,3 +129,1563 @@ async fn non_destruction() {
     std::mem::drop(lock);
     assert_eq!(COUNT.load(Ordering::SeqCst), 1);
 }
+
+/// Group operations should reach every member.
+#[tokio::test]
+async fn group_broadcast() {
+    let group = SlockGroup::new_group();
+    let first = Slock::new(1i32);
+    group.push(first.clone()).await;
+    group.push(Slock::new(2i32)).await;
+    group.broadcast_set(|v| v * 10).await;
+    assert_eq!(first.get().await, 10);
+    assert_eq!(group.collect_map(|v| *v).await.unwrap(), vec![10, 20]);
+    assert!(group.any(|v| *v == 20).await.unwrap());
+    assert!(!group.all(|v| *v == 20).await.unwrap());
+}
+
+/// Ordered helpers should only replace the value when it moves.
+#[tokio::test]
+async fn ordering() {
+    let lock = Slock::new(5i32);
+    assert!(lock.fetch_max(8).await.unwrap());
+    assert!(!lock.fetch_max(7).await.unwrap());
+    assert!(lock.fetch_min(2).await.unwrap());
+    assert!(lock.clamp_in_place(3, 4).await.unwrap());
+    assert!(!lock.clamp_in_place(3, 4).await.unwrap());
+    assert_eq!(lock.get().await, 3);
+}
+
+/// Checked math should refuse to overflow and leave the value alone.
+#[tokio::test]
+async fn checked_math() {
+    let lock = Slock::new(250u8);
+    assert_eq!(lock.checked_add(10).await, Err(Overflow));
+    assert_eq!(lock.get().await, 250);
+    assert_eq!(lock.checked_add(5).await, Ok(255));
+    assert_eq!(lock.wrapping_add(2).await, 1);
+    assert_eq!(lock.saturating_sub(5).await, 0);
+    assert_eq!(lock.checked_mul(3).await, Ok(0));
+}
+
+/// A lock holding a `Result` should store the outcome of a retried operation.
+#[tokio::test]
+async fn results() {
+    let lock: Slock<Result<i32, &str>> = Slock::new(Err("unloaded"));
+    assert!(lock.is_err().await);
+
+    let mut calls = 0;
+    let loaded = lock
+        .retry_with(3, || {
+            calls += 1;
+            let outcome = if calls < 2 { Err("flaky") } else { Ok(7) };
+            async move { outcome }
+        })
+        .await;
+    assert!(loaded);
+    assert_eq!(calls, 2);
+    assert_eq!(lock.ok_clone().await, Some(7));
+    assert_eq!(lock.map_ok(|v| v * 2).await.unwrap(), Some(14));
+
+    lock.set_err("gone").await;
+    assert!(!lock.is_ok().await);
+}
+
+/// A recorded lock should be able to answer what it held in the past.
+#[cfg(feature = "record")]
+#[tokio::test]
+async fn recording() {
+    let lock = Slock::new(1i32);
+    lock.record(2).await;
+    lock.set(|v| v + 1).await;
+    let middle = tokio::time::Instant::now();
+    lock.set(|v| v + 1).await;
+
+    let timeline = lock.timeline().await;
+    assert_eq!(timeline.len(), 2);
+    assert_eq!(timeline[1].value, 3);
+    assert_eq!(lock.replay_at(middle).await, Some(2));
+}
+
+/// Several keys should be readable from one view of the map.
+#[tokio::test]
+async fn map_bulk_reads() {
+    let map = SlockMap::new_map();
+    map.insert("a", |_| 1).await;
+    map.insert("b", |_| 2).await;
+
+    let found = map.get_many(&["a", "c"]).await;
+    assert!(found[0].is_some());
+    assert!(found[1].is_none());
+
+    let snapshot = map.snapshot().await.unwrap();
+    assert_eq!(snapshot.len(), 2);
+    assert_eq!(snapshot["b"], 2);
+
+    map.from_key("a")
+        .await
+        .unwrap()
+        .set(|_| panic!("setter"))
+        .await;
+    assert!(matches!(map.snapshot().await, Err(Error::Poisoned)));
+}
+
+/// Every entry of a map should be visited.
+#[tokio::test]
+async fn map_iteration() {
+    use futures::StreamExt;
+
+    let map = SlockMap::new_map();
+    map.insert(1, |_| 10).await;
+    map.insert(2, |_| 20).await;
+
+    map.for_each(|_, inner| async move { inner.set(|v| v + 1).await })
+        .await;
+
+    let mut total = 0;
+    let mut entries = Box::pin(map.iter().await);
+    while let Some((key, inner)) = entries.next().await {
+        total += key + inner.get().await;
+    }
+    assert_eq!(total, 3 + 11 + 21);
+}
+
+/// Two-level maps should create their inner maps on demand.
+#[tokio::test]
+async fn nested_maps() {
+    use std::collections::HashMap;
+
+    let map: SlockMap<u8, HashMap<u8, Slock<i32>>> = SlockMap::new_map();
+    map.at(1).await.at(2).await.set(|v| v + 3).await;
+    map.insert_path(1, 2, |v| v.unwrap() * 2).await;
+    map.insert_path(4, 5, |_| 9).await;
+
+    assert_eq!(map.get_path(1, 2).await.unwrap().get().await, 6);
+    assert_eq!(map.get_path(4, 5).await.unwrap().get().await, 9);
+    assert!(map.get_path(4, 6).await.is_none());
+}
+
+/// Copy-on-write snapshots should share data until one side is modified.
+#[tokio::test]
+async fn copy_on_write() {
+    use std::sync::Arc;
+
+    let lock = Slock::new(Arc::new(vec![1, 2, 3]));
+    let snapshot = lock.clone_cow().await;
+    assert!(Arc::ptr_eq(
+        &lock.get_clone().await,
+        &snapshot.get_clone().await
+    ));
+
+    lock.set_cow(|v| v.push(4)).await;
+    assert_eq!(lock.get_clone().await.len(), 4);
+    assert_eq!(snapshot.get_clone().await.len(), 3);
+}
+
+/// Entries only held by the map should be pruned, entries in use should not.
+#[cfg(all(feature = "rt", feature = "time"))]
+#[tokio::test]
+async fn map_pruning() {
+    let map = SlockMap::new_map();
+    map.insert(1, |_| 'a').await;
+    map.insert(2, |_| 'b').await;
+    map.insert(3, |_| 'c').await;
+    let held = map.from_key(1).await.unwrap();
+
+    assert_eq!(map.prune_idle(Duration::from_secs(60)).await, 0);
+    assert_eq!(map.prune_unreferenced().await, 2);
+    assert_eq!(held.get().await, 'a');
+    assert!(map.from_key(1).await.is_some());
+
+    std::mem::drop(held);
+    assert_eq!(map.prune_idle(Duration::ZERO).await, 1);
+
+    // Pruners and tick hooks on the same map don't keep each other running
+    let tick = Duration::from_millis(5);
+    let tasks = [
+        map.spawn_pruner(Duration::ZERO, tick),
+        map.hook_per_tick(tick, |_| {}),
+    ];
+    drop(map);
+    for task in tasks {
+        tokio::time::timeout(Duration::from_millis(100), task)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
+
+/// Maps should work the same with a custom hasher.
+#[tokio::test]
+async fn map_custom_hasher() {
+    use std::{collections::hash_map::DefaultHasher, hash::BuildHasherDefault};
+
+    let map: SlockMap<u8, u8, BuildHasherDefault<DefaultHasher>> =
+        SlockMap::with_hasher(Default::default());
+    map.insert(1, |_| 2).await;
+    assert_eq!(map.from_key(1).await.unwrap().get().await, 2);
+    assert_eq!(map.snapshot().await.unwrap()[&1], 2);
+}
+
+/// Locks should be comparable by content without cloning.
+#[tokio::test]
+async fn equality() {
+    let a = Slock::new(String::from("state"));
+    let b = Slock::new(String::from("state"));
+    assert!(slock::eq(&a, &b).await);
+    assert!(slock::eq(&b, &a).await);
+    assert!(slock::eq(&a, &a).await);
+
+    b.set(|_| String::from("changed")).await;
+    assert!(!slock::eq(&a, &b).await);
+    assert!(b.content_eq(&String::from("changed")).await);
+}
+
+/// Every acquisition and set should be counted.
+#[tokio::test]
+async fn metrics() {
+    let lock = Slock::new(0i32);
+    lock.set(|v| v + 1).await;
+    lock.get().await;
+    let metrics = lock.metrics();
+    assert_eq!(metrics.acquisitions, 2);
+    assert_eq!(metrics.version, 1);
+    assert_eq!(metrics.timeouts, 0);
+}
+
+/// Validated locks should refuse invalid values from every kind of write.
+#[tokio::test]
+async fn validation() {
+    let lock = Slock::new_validated(5u8, |v| if *v < 10 { Ok(()) } else { Err("too big") });
+    assert!(lock.try_set(|v| v + 1).await.is_ok());
+    let Err(Error::Validation(error)) = lock.try_set(|_| 20).await else {
+        panic!("validator should reject the value");
+    };
+    assert_eq!(error.into_inner().to_string(), "too big");
+    lock.set(|_| 30).await;
+    assert_eq!(lock.checked_add(7).await, Ok(6));
+    assert!(!lock.fetch_max(12).await.unwrap());
+    assert_eq!(lock.get().await, 6);
+    assert_eq!(lock.metrics().version, 1);
+}
+
+/// Builder options should carry through to the lock.
+#[tokio::test]
+async fn builder() {
+    let lock = Slock::builder()
+        .named("counter")
+        .metrics(false)
+        .validator(|v: &i32| if *v >= 0 { Ok(()) } else { Err("negative") })
+        .build(1);
+    assert_eq!(lock.name(), Some("counter"));
+    assert!(lock.try_set(|v| v - 2).await.is_err());
+    assert_eq!(lock.get().await, 1);
+    assert_eq!(lock.metrics().acquisitions, 0);
+    assert_eq!(Slock::new(0).name(), None);
+}
+
+/// Concurrent loads of the same key should only call the loader once.
+#[tokio::test]
+async fn loader_single_flight() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let loader = SlockLoader::new(move |key: u32| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            tokio::task::yield_now().await;
+            key * 2
+        }
+    });
+
+    let (a, b) = tokio::join!(loader.get_or_load(4), loader.get_or_load(4));
+    let (a, b) = (a.unwrap(), b.unwrap());
+    assert_eq!(a.get().await, 8);
+    assert!(a.has_other_refs() && b.ref_count() == a.ref_count());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    loader.invalidate(4).await;
+    loader.get_or_load(4).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+/// Changes to loaded values should be written back, alongside the values' own hooks.
+#[cfg(feature = "rt")]
+#[tokio::test]
+async fn loader_write_back() {
+    let written = Slock::new(Vec::new());
+    let sink = written.clone();
+    let loader = SlockLoader::new(|_: u8| async { 0i32 }).with_write_back(move |key, value| {
+        let sink = sink.clone();
+        async move { sink.push((key, value)).await }
+    });
+
+    let value = loader.get_or_load(1).await.unwrap();
+    value.hook(|_| {}).await;
+    value.set(|v| v + 5).await;
+    written.wait_until(|w| !w.is_empty()).await;
+    assert_eq!(written.get_clone().await, vec![(1, 5)]);
+}
+
+/// Concurrent recomputations should share a single run.
+#[tokio::test]
+async fn recompute_coalescing() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let runs = AtomicUsize::new(0);
+    let lock = Slock::new(1u64);
+    let compute = |old: u64| {
+        runs.fetch_add(1, Ordering::SeqCst);
+        async move {
+            tokio::task::yield_now().await;
+            old * 10
+        }
+    };
+
+    let (a, b, c) = tokio::join!(
+        lock.recompute_once(compute),
+        lock.recompute_once(compute),
+        lock.recompute_once(compute),
+    );
+    assert_eq!((a, b, c), (10, 10, 10));
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+/// Partitioned vectors should recombine in their original order.
+#[tokio::test]
+async fn partitioning() {
+    let lock = Slock::new((0..10).collect::<Vec<i32>>());
+    let parts = lock.partition(3).await;
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[0].get_clone().await, vec![0, 1, 2, 3]);
+    assert_eq!(parts[2].get_clone().await, vec![7, 8, 9]);
+    assert!(lock.get_clone().await.is_empty());
+
+    parts[1].push(100).await;
+    lock.recombine(parts).await;
+    assert_eq!(
+        lock.get_clone().await,
+        vec![0, 1, 2, 3, 4, 5, 6, 100, 7, 8, 9]
+    );
+}
+
+/// Context values should be moved into the closures.
+#[tokio::test]
+async fn context_passing() {
+    let lock = Slock::new(vec![String::from("a")]);
+    lock.set_with(String::from("b"), |mut v, s| {
+        v.push(s);
+        v
+    })
+    .await;
+    let found = lock
+        .map_with(String::from("b"), |v, s| v.contains(&s))
+        .await
+        .unwrap();
+    assert!(found);
+}
+
+/// Piped transformations should compose in order.
+#[tokio::test]
+async fn piping() {
+    let lock = Slock::new(3i32);
+    let text = lock
+        .pipe(|v| v * 2)
+        .pipe(|v| v + 1)
+        .pipe(|v| v.to_string())
+        .resolve()
+        .await
+        .unwrap();
+    assert_eq!(text, "7");
+}
+
+/// Mocks should record sets and follow their script.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn mocking() {
+    use futures::StreamExt;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let mock = testing::MockSlock::from(
+        Slock::builder()
+            .timeout(Duration::from_millis(10))
+            .build(1i32),
+    );
+    mock.set(|v| v + 1).await;
+    mock.queue_read(10);
+    assert_eq!(mock.get_clone().await.unwrap(), 10);
+    assert_eq!(mock.get_clone().await.unwrap(), 2);
+
+    mock.delay_acquisitions(Duration::from_millis(50));
+    assert!(matches!(mock.try_set(|v| v + 1).await, Err(Error::Timeout)));
+    mock.assert_set_called(2);
+    assert_eq!(mock.lock().metrics().timeouts, 1);
+
+    mock.delay_acquisitions(Duration::ZERO);
+    let hooked = Arc::new(AtomicUsize::new(0));
+    let counter = hooked.clone();
+    mock.hook(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    })
+    .await;
+    let mut changes = Box::pin(mock.changes().await);
+    let (doubled, stored) = mock.update(|v| (Some(v * 2), v * 2)).await.unwrap();
+    assert_eq!((doubled, stored), (4, true));
+    assert!(!mock.update(|_| (None, ())).await.unwrap().1);
+    mock.assert_set_called(4);
+    assert_eq!(changes.next().await, Some((2, 4)));
+    assert_eq!(hooked.load(Ordering::SeqCst), 1);
+    mock.queue_read(7);
+    assert_eq!(mock.get().await.unwrap(), 7);
+    assert_eq!(mock.get().await.unwrap(), 4);
+}
+
+static_slock! {
+    static VISITS: u32 = 10;
+}
+
+/// Static locks should start from their initializer and keep their value.
+#[tokio::test]
+async fn static_locks() {
+    VISITS.set(|v| v + 1).await;
+    assert_eq!(VISITS.get().await, 11);
+}
+
+/// Reads that can't acquire the lock in time should fall back.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn read_fallbacks() {
+    let lock = Slock::builder()
+        .timeout(Duration::from_millis(20))
+        .build(5i32);
+    assert_eq!(lock.get_or(0).await, 5);
+
+    let busy = lock.clone();
+    let writer = in_thread(async move {
+        busy.set(|v| {
+            std::thread::sleep(Duration::from_millis(200));
+            v + 1
+        })
+        .await
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(lock.get_or_else(|| -1).await, -1);
+    assert_eq!(lock.get_or_default().await, 0);
+    writer.join().unwrap();
+    assert_eq!(lock.get_or_default().await, 6);
+}
+
+/// Writes beyond a rejecting queue's capacity should fail straight away.
+#[tokio::test]
+async fn write_queues() {
+    let lock = Slock::builder()
+        .write_queue(1, WhenFull::Reject)
+        .build(0i32);
+    let slow = lock.clone();
+    let writer = in_thread(async move {
+        slow.try_set(|v| {
+            std::thread::sleep(Duration::from_millis(200));
+            v + 1
+        })
+        .await
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(matches!(
+        lock.try_set(|v| v + 10).await,
+        Err(Error::QueueFull)
+    ));
+    writer.join().unwrap().unwrap();
+
+    let waiting = Slock::with_write_queue(0i32, 1);
+    tokio::join!(waiting.set(|v| v + 1), waiting.set(|v| v + 1));
+    assert_eq!(waiting.get().await, 2);
+    assert_eq!(lock.get().await, 1);
+}
+
+/// Two-phase updates should apply both values or neither.
+#[tokio::test]
+async fn two_phase_commit() {
+    let alice = Slock::new(100i64);
+    let bob = Slock::new_validated(0i64, |v| if *v <= 60 { Ok(()) } else { Err("cap") });
+    let transfer = |amount: i64| {
+        move |a: &i64, b: &i64| {
+            if *a >= amount {
+                Ok((a - amount, b + amount))
+            } else {
+                Err("insufficient funds")
+            }
+        }
+    };
+
+    slock::two_phase(&alice, &bob)
+        .prepare(transfer(50))
+        .commit()
+        .await
+        .unwrap();
+    assert!(slock::two_phase(&alice, &bob)
+        .prepare(transfer(80))
+        .commit()
+        .await
+        .is_err());
+    assert!(slock::two_phase(&bob, &alice)
+        .prepare(transfer(40))
+        .commit()
+        .await
+        .is_ok());
+    assert!(slock::two_phase(&alice, &bob)
+        .prepare(transfer(60))
+        .commit()
+        .await
+        .is_err());
+    assert_eq!((alice.get().await, bob.get().await), (90, 10));
+}
+
+/// Forks should stay independent until merged.
+#[tokio::test]
+async fn forking() {
+    let parent = Slock::new(10i32);
+    let child = parent.fork().await;
+    child.set(|v| v + 5).await;
+    parent.set(|v| v + 1).await;
+    assert_eq!(parent.get().await, 11);
+
+    child
+        .merge_into(&parent, MergeStrategy::custom(|p: i32, c: &i32| p.max(*c)))
+        .await
+        .unwrap();
+    assert_eq!(parent.get().await, 15);
+    child.set(|_| 0).await;
+    child
+        .merge_into(&parent, MergeStrategy::LastWriteWins)
+        .await
+        .unwrap();
+    assert_eq!(parent.get().await, 0);
+}
+
+/// Collection helpers should work the same over any collection.
+#[tokio::test]
+async fn collections() {
+    async fn size<C: SlockCollection>(lock: &Slock<C>) -> usize {
+        lock.len().await
+    }
+
+    let list = Slock::new(vec![1i32, 20, 3, 40]);
+    assert_eq!(list.retain(|v| *v >= 10).await, 2);
+    assert_eq!(size(&list).await, 2);
+
+    let set = Slock::new(std::collections::HashSet::from([5i32, 50]));
+    set.retain(|v| *v > 10).await;
+    assert_eq!(size(&set).await, 1);
+
+    let map = Slock::new(std::collections::HashMap::from([(1, 'a'), (2, 'b')]));
+    map.retain(|(k, _)| *k == 2).await;
+    assert_eq!(map.len().await, 1);
+    map.clear().await;
+    assert!(map.is_empty().await);
+}
+
+/// Deep clones should give up if the lock is held for too long.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn fallible_deep_clones() {
+    let lock = Slock::new(String::from("state"));
+    let copy = lock
+        .try_clone_deep(Duration::from_millis(50))
+        .await
+        .unwrap();
+    copy.set(|_| String::new()).await;
+    assert_eq!(lock.get_clone().await, "state");
+
+    let busy = lock.clone();
+    let writer = in_thread(async move {
+        busy.set(|v| {
+            std::thread::sleep(Duration::from_millis(200));
+            v
+        })
+        .await
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let copied = lock
+        .try_clone_deep_with(Duration::from_millis(20), |v| v.to_uppercase())
+        .await;
+    assert!(matches!(copied, Err(Error::Timeout)));
+    writer.join().unwrap();
+}
+
+/// Dropping a scope should cancel its tasks and release their handles.
+#[cfg(feature = "rt")]
+#[tokio::test]
+async fn scoped_tasks() {
+    let lock = Slock::new(0i32);
+    let mut scope = lock.scope();
+    scope
+        .spawn(|lock| async move { lock.set(|v| v + 1).await })
+        .spawn(|lock| async move { lock.set(|v| v + 1).await });
+    scope.join().await;
+    assert_eq!(lock.get().await, 2);
+
+    let mut scope = lock.scope();
+    scope.spawn(|lock| async move {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        lock.set(|_| -1).await;
+    });
+    tokio::task::yield_now().await;
+    drop(scope);
+    tokio::task::yield_now().await;
+    assert!(!lock.has_other_refs());
+}
+
+/// Locks should still work from executors other than tokio.
+#[cfg(feature = "time")]
+#[test]
+fn without_runtime() {
+    let lock = Slock::new(1i32);
+    futures::executor::block_on(async {
+        lock.set(|v| v + 1).await;
+        assert_eq!(lock.map(|v| *v).await.unwrap(), 2);
+        assert_eq!(lock.get_or(0).await, 2);
+
+        // Timed features wait without tokio's timer
+        let limiter = util::RateLimiter::new(1, Duration::from_millis(20));
+        limiter.acquire(1).await;
+        limiter.acquire(1).await;
+        assert_eq!(limiter.available().await, 0);
+    });
+}
+
+/// Locks should still work in a tokio runtime without the time driver.
+#[cfg(feature = "time")]
+#[test]
+fn without_time_driver() {
+    let lock = Slock::new(1i32);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        lock.set(|v| v + 1).await;
+        assert_eq!(lock.map(|v| *v).await.unwrap(), 2);
+
+        // Other writers wait out the lease without tokio's timer
+        let lease = lock.lease(Duration::from_millis(20)).await;
+        lease.set(|v| v + 1).await;
+        lock.set(|v| v * 10).await;
+        assert_eq!(lock.get().await, 30);
+    });
+}
+
+/// Bulk loads should land in a single write.
+#[tokio::test]
+async fn bulk_loading() {
+    let lock = Slock::new(vec![0u32]);
+    lock.extend_from_iter(1..1000).await;
+    assert_eq!(lock.len().await, 1000);
+    lock.replace_from_iter([7, 8]).await;
+    assert_eq!(lock.get_clone().await, vec![7, 8]);
+    assert_eq!(lock.metrics().version, 2);
+
+    let set: Slock<std::collections::HashSet<u8>> = Slock::new(Default::default());
+    set.extend_from_iter([1, 1, 2]).await;
+    assert_eq!(set.len().await, 2);
+}
+
+/// Sessions should remember the version of their own writes.
+#[tokio::test]
+async fn sessions() {
+    let lock = Slock::new(0i32);
+    let session = lock.session();
+    assert_eq!(session.version(), 0);
+    lock.set(|v| v + 1).await;
+    session.set(|v| v + 10).await;
+    assert_eq!(session.version(), 2);
+    assert_eq!(session.get().await, 11);
+}
+
+/// Debug dumps should describe the value and the lock around it.
+#[tokio::test]
+async fn debug_dump() {
+    let lock = Slock::builder().named("score").build(3i32);
+    let _other = lock.clone();
+    lock.set(|v| v + 1).await;
+    lock.inspect(|v| assert_eq!(*v, 4)).await;
+
+    let dump = lock.debug_dump().await;
+    assert!(dump.contains("name: Some(\"score\")"));
+    assert!(dump.contains("version: 1"));
+    assert!(dump.contains("ref_count: 2"));
+    assert!(dump.contains("state: unlocked"));
+    assert!(dump.contains("value: 4"));
+}
+
+/// Ordered queries over a map should find the right entries.
+#[tokio::test]
+async fn map_queries() {
+    let scores = SlockMap::new_map();
+    assert_eq!(scores.max_by_key(|v: &i32| *v).await.unwrap(), None);
+    for (name, score) in [("bob", 10), ("alice", 30), ("carol", 20)] {
+        scores.insert(name, |_| score).await;
+    }
+
+    assert_eq!(
+        scores.max_by_key(|v| *v).await.unwrap(),
+        Some(("alice", 30))
+    );
+    assert_eq!(scores.min_by_key(|v| *v).await.unwrap(), Some(("bob", 10)));
+    assert_eq!(
+        scores.find(|k, _| k.starts_with('c')).await.unwrap(),
+        Some(("carol", 20))
+    );
+    assert_eq!(scores.find(|_, v| *v > 50).await.unwrap(), None);
+
+    let panicked = scores.find(|_, _| panic!("predicate")).await;
+    assert!(matches!(panicked, Err(Error::ClosurePanicked)));
+    assert_eq!(
+        scores.max_by_key(|v| *v).await.unwrap(),
+        Some(("alice", 30))
+    );
+}
+
+/// Joins should read every listed lock, in any order.
+#[tokio::test]
+async fn join_macro() {
+    let a = Slock::new(1i32);
+    let b = Slock::new(2u8);
+    let c = Slock::new(String::from("three"));
+
+    let forwards = slock_join!(a, b, c => |a, b, c| format!("{a}{b}{c}")).await;
+    let backwards = slock_join!(c, b, a => |c, b, a| format!("{a}{b}{c}")).await;
+    assert_eq!(forwards, "12three");
+    assert_eq!(forwards, backwards);
+
+    a.set(|v| v + 1).await;
+    assert_eq!(slock_join!(a => |v| *v).await, 2);
+}
+
+/// Arena keys should stop working once their value is removed, even if the slot is reused.
+#[tokio::test]
+async fn arena() {
+    let arena = SlockArena::with_capacity(2);
+    let a = arena.insert(1i32).await.unwrap();
+    let b = arena.insert(2).await.unwrap();
+    assert_eq!(arena.insert(3).await, Err(3));
+    assert_eq!(arena.len(), 2);
+
+    assert!(arena.set(a, |v| v + 10).await.unwrap());
+    assert_eq!(arena.get_clone(a).await.unwrap(), Some(11));
+    assert_eq!(arena.remove(a).await, Some(11));
+    assert!(!arena.contains(a).await);
+
+    let c = arena.insert(4).await.unwrap();
+    assert_ne!(a, c);
+    assert!(!arena.set(a, |v| v + 10).await.unwrap());
+    assert_eq!(arena.map(c, |v| *v).await.unwrap(), Some(4));
+    assert_eq!(arena.get_clone(b).await.unwrap(), Some(2));
+
+    // A panicking setter frees its slot instead of leaking it
+    let panicked = arena.set(c, |_| panic!("setter")).await;
+    assert!(matches!(panicked, Err(Error::ClosurePanicked)));
+    assert!(!arena.contains(c).await);
+    assert_eq!(arena.len(), 1);
+    assert!(arena.insert(5).await.is_ok());
+    let panicked = arena.map(b, |_| -> i32 { panic!("mapper") }).await;
+    assert!(matches!(panicked, Err(Error::ClosurePanicked)));
+    assert_eq!(arena.get_clone(b).await.unwrap(), Some(2));
+}
+
+/// Every mutator should be applied, each reporting metrics from after its own write.
+#[cfg(feature = "rt")]
+#[tokio::test]
+async fn spawn_mutators() {
+    let total = Slock::new(0u64);
+    let mut tasks = tokio::task::JoinSet::new();
+    let spawned = total.spawn_mutators(&mut tasks, (1..=10).map(|n| move |v: u64| v + n));
+    assert_eq!(spawned, 10);
+
+    let mut versions = Vec::new();
+    while let Some(result) = tasks.join_next().await {
+        versions.push(result.unwrap().unwrap().version);
+    }
+    versions.sort();
+    assert_eq!(versions.last(), Some(&10));
+    assert!(versions.iter().all(|v| (1..=10).contains(v)));
+    assert_eq!(total.get().await, 55);
+}
+
+/// Late observers should be able to catch up on the last few values.
+#[cfg(feature = "record")]
+#[tokio::test]
+async fn recent_values() {
+    let lock = Slock::new(0i32);
+    assert!(lock.recent(5).await.is_empty());
+    lock.record(3).await;
+    for v in 1..=5 {
+        lock.set(|_| v).await;
+    }
+    assert_eq!(lock.recent(2).await, vec![4, 5]);
+    assert_eq!(lock.recent(10).await, vec![3, 4, 5]);
+}
+
+/// RCU updates should retry when another write lands during the computation.
+#[tokio::test]
+async fn rcu_updates() {
+    let lock = Slock::new(1i32);
+    let mut attempts = 0;
+    lock.rcu_update(|v| {
+        attempts += 1;
+        if attempts == 1 {
+            // Sneak in a write while the computation runs
+            let other = lock.clone();
+            in_thread(async move { other.set(|v| v * 10).await })
+                .join()
+                .unwrap();
+        }
+        v + 1
+    })
+    .await
+    .unwrap();
+    assert_eq!(attempts, 2);
+    assert_eq!(lock.get().await, 11);
+}
+
+/// Writes with a token should fail if any lock it covers was written to since.
+#[tokio::test]
+async fn read_tokens() {
+    let a = Slock::new(1i32);
+    let b = Slock::new(String::from("b"));
+
+    let token = a.read_token().await.and(b.read_token().await);
+    a.set_with_token(&token, |v| v + 1).await.unwrap();
+    // The first write changed `a`
+    assert!(matches!(
+        b.set_with_token(&token, |v| v + "!").await,
+        Err(Error::Conflict)
+    ));
+
+    let token = a.read_token().await.and(b.read_token().await);
+    b.set(|v| v + "?").await;
+    assert!(matches!(
+        a.set_with_token(&token, |v| v * 10).await,
+        Err(Error::Conflict)
+    ));
+    assert_eq!(a.get().await, 2);
+    assert_eq!(b.get_clone().await, "b?");
+}
+
+/// Paused writes should wait, and be cancellable, until writes resume.
+#[tokio::test]
+async fn paused_writes() {
+    let lock = Slock::new(0i32);
+    lock.pause_writes();
+    tokio::select! {
+        _ = lock.set(|v| v + 1) => panic!("writes are paused"),
+        _ = tokio::time::sleep(Duration::from_millis(10)) => {}
+    }
+    assert_eq!(lock.get().await, 0);
+
+    let other = lock.clone();
+    let write = tokio::spawn(async move { other.set(|v| v + 1).await });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(lock.get().await, 0);
+    lock.resume_writes();
+    write.await.unwrap();
+    assert_eq!(lock.get().await, 1);
+}
+
+/// Leaked locks should share their data with the handles they came from.
+#[tokio::test]
+async fn leaked_locks() {
+    let lock = Slock::new(1i32);
+    let leaked: &'static Slock<i32> = lock.clone().leak();
+    leaked.set(|v| v + 1).await;
+    assert_eq!(lock.get().await, 2);
+    assert_eq!(lock.ref_count(), 2);
+}
+
+/// Shared locks should hand out the same allocation until it's written to.
+#[tokio::test]
+async fn shared_values() {
+    let lock = Slock::new_shared(vec![1, 2, 3]);
+    let first = lock.get_clone().await;
+    assert!(std::sync::Arc::ptr_eq(&first, &lock.get_clone().await));
+
+    lock.set_cow(|v| v.push(4)).await;
+    assert_eq!(*first, vec![1, 2, 3]);
+    assert_eq!(*lock.get_clone().await, vec![1, 2, 3, 4]);
+}
+
+/// Panicking closures should be reported as errors, leaving the lock usable.
+#[tokio::test]
+async fn closure_panics() {
+    let lock = Slock::builder()
+        .validator(|_: &Vec<i32>| Ok::<(), &str>(()))
+        .build(vec![1, 2, 3]);
+    let mapped: Result<i32, Error> = lock.map(|_| panic!("mapper")).await;
+    assert!(matches!(mapped, Err(Error::ClosurePanicked)));
+    let cloned: Result<i32, Error> = lock.map_cloned(|_| panic!("projection")).await;
+    assert!(matches!(cloned, Err(Error::ClosurePanicked)));
+
+    let set = lock.try_set(|_| panic!("setter")).await;
+    assert!(matches!(set, Err(Error::ClosurePanicked)));
+    assert_eq!(lock.get_clone().await, vec![1, 2, 3]);
+    assert_eq!(lock.metrics().version, 0);
+}
+
+/// Locks should follow watch channels, and watch channels should follow locks.
+#[cfg(feature = "rt")]
+#[tokio::test]
+async fn watch_interop() {
+    let (sender, receiver) = tokio::sync::watch::channel(1i32);
+    let mirror = Slock::from_watch(receiver);
+    assert_eq!(mirror.get().await, 1);
+    sender.send(2).unwrap();
+    mirror.changed().await;
+    assert_eq!(mirror.get().await, 2);
+
+    let lock = Slock::new(String::from("a"));
+    let mut follower = lock.to_watch().await;
+    assert_eq!(*follower.borrow(), "a");
+    lock.set(|v| v + "b").await;
+    follower.changed().await.unwrap();
+    assert_eq!(*follower.borrow(), "ab");
+
+    // The forwarder stops once the lock is gone
+    drop(lock);
+    assert!(follower.changed().await.is_err());
+}
+
+slock_key! {
+    struct UserId(u64);
+    struct GuildId(pub u64);
+}
+
+/// Newtype keys should index maps like their ids, without mixing kinds.
+#[tokio::test]
+async fn typed_keys() {
+    let names: SlockMap<UserId, &str> = SlockMap::new_map();
+    let guilds: SlockMap<GuildId, u32> = SlockMap::new_map();
+    names.insert(UserId(1), |_| "bob").await;
+    guilds.insert(1.into(), |_| 10).await;
+
+    assert_eq!(names.from_key(1.into()).await.unwrap().get().await, "bob");
+    assert_eq!(guilds.from_key(GuildId(1)).await.unwrap().get().await, 10);
+    assert_eq!(UserId(1).to_string(), "1");
+}
+
+/// Draining workers should each take different items, waiting when the queue is empty.
+#[tokio::test]
+async fn drain_streams() {
+    use futures::StreamExt;
+    use std::collections::VecDeque;
+
+    let jobs = Slock::new(VecDeque::from([1, 2]));
+    let first: Vec<i32> = jobs.drain_stream().take(2).collect().await;
+    assert_eq!(first, vec![1, 2]);
+
+    let workers: Vec<_> = (0..2)
+        .map(|_| tokio::spawn(jobs.drain_stream().take(2).collect::<Vec<i32>>()))
+        .collect();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    for job in 3..=6 {
+        jobs.set(|mut q| {
+            q.push_back(job);
+            q
+        })
+        .await;
+    }
+    let mut taken: Vec<i32> = Vec::new();
+    for worker in workers {
+        taken.extend(worker.await.unwrap());
+    }
+    taken.sort();
+    assert_eq!(taken, vec![3, 4, 5, 6]);
+}
+
+/// Vector elements should be locked individually, and removable by swapping.
+#[tokio::test]
+async fn slock_vec() {
+    let vec = SlockVec::new();
+    for value in [1, 2, 3] {
+        vec.push(value).await;
+    }
+    let first = vec.get(0).await.unwrap();
+    first.set(|v| v * 10).await;
+
+    let removed = vec.swap_remove(0).await.unwrap();
+    assert_eq!(removed.get().await, 10);
+    assert_eq!(vec.get(0).await.unwrap().get().await, 3);
+    assert_eq!(vec.len().await, 2);
+    assert!(vec.swap_remove(5).await.is_none());
+    assert!(vec.get(2).await.is_none());
+
+    vec.lock().broadcast_set(|v| v + 1).await;
+    assert_eq!(vec.lock().collect_map(|v| *v).await.unwrap(), vec![4, 3]);
+
+    let group = SlockGroup::new_group();
+    group.push(Slock::new(7)).await;
+    let vec = SlockVec::from(group.clone());
+    vec.push(8).await;
+    assert_eq!(group.collect_map(|v| *v).await.unwrap(), vec![7, 8]);
+}
+
+/// Deadlines should cover waiting for the lock, and count setters that run past them.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn set_with_deadline() {
+    use std::time::Instant;
+
+    let lock = Slock::new(0);
+    let soon = || Instant::now() + Duration::from_millis(20);
+
+    lock.pause_writes();
+    let result = lock.set_with_deadline(soon(), |v| v + 1).await;
+    assert!(matches!(result, Err(Error::Timeout)));
+    lock.resume_writes();
+
+    let past = Instant::now() - Duration::from_millis(1);
+    assert!(lock.set_with_deadline(past, |v| v + 1).await.is_err());
+    assert_eq!(lock.get().await, 0);
+
+    lock.set_with_deadline(soon(), |v| {
+        std::thread::sleep(Duration::from_millis(40));
+        v + 1
+    })
+    .await
+    .unwrap();
+    assert_eq!(lock.get().await, 1);
+    assert_eq!(lock.metrics().overruns, 1);
+    assert_eq!(lock.metrics().timeouts, 2);
+}
+
+/// Timed out reads should say which lock stalled, and for how long it had been written to.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn get_clone_timeout() {
+    let lock = Slock::builder().named("config").build(1);
+    let writer = in_thread({
+        let lock = lock.clone();
+        async move {
+            lock.set(|v| {
+                std::thread::sleep(Duration::from_millis(200));
+                v + 1
+            })
+            .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    match lock.get_clone_timeout(Duration::from_millis(20)).await {
+        Err(Error::Stalled { name, held_for, .. }) => {
+            assert_eq!(name.as_deref(), Some("config"));
+            assert!(held_for.unwrap() >= Duration::from_millis(50));
+        }
+        other => panic!("expected a stall, got {other:?}"),
+    }
+    writer.join().unwrap();
+    assert_eq!(
+        lock.get_clone_timeout(Duration::from_millis(20))
+            .await
+            .unwrap(),
+        2
+    );
+}
+
+/// Bulk inserts should add new keys in one write, and set the locks of existing ones.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn map_insert_many() {
+    let map = SlockMap::new_map();
+    map.insert(1, |_| "one").await;
+    let one = map.from_key(1).await.unwrap();
+
+    let added = map.insert_many([(1, "uno"), (2, "dos"), (3, "tres")]).await;
+    assert_eq!(added, 2);
+    assert_eq!(map.metrics().version, 2);
+    assert_eq!(one.get().await, "uno");
+    assert_eq!(map.from_key(3).await.unwrap().get().await, "tres");
+
+    // Waiting on a busy entry shouldn't keep the map locked
+    let lease = one.lease(Duration::from_secs(1)).await;
+    let (added, found) = tokio::join!(map.insert_many([(1, "eins")]), async {
+        let found = tokio::time::timeout(Duration::from_millis(100), map.from_key(2)).await;
+        drop(lease);
+        found
+    });
+    assert_eq!(added, 0);
+    assert!(found.unwrap().is_some());
+    assert_eq!(one.get().await, "eins");
+}
+
+/// Ordered pushes should commit in the order they were started, skipping abandoned ones.
+#[tokio::test]
+async fn ordered_pushes() {
+    let log = Slock::new(Vec::new());
+    let ordered = log.ordered();
+
+    let abandoned = ordered.push(-1);
+    let pushes: Vec<_> = (0..20).map(|i| ordered.push(i)).collect();
+    drop(abandoned);
+    futures::future::join_all(pushes.into_iter().rev()).await;
+
+    assert_eq!(log.get_clone().await, (0..20).collect::<Vec<_>>());
+}
+
+/// Bounded writes should give up without running the setter when the lock stays busy.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn try_set_for() {
+    let lock = Slock::new(0);
+    lock.try_set_for(Duration::ZERO, |v| v + 1).await.unwrap();
+
+    lock.pause_writes();
+    let result = lock
+        .try_set_for(Duration::from_millis(20), |_| panic!("setter ran"))
+        .await;
+    assert!(matches!(result, Err(Error::Timeout)));
+    lock.resume_writes();
+    assert_eq!(lock.get().await, 1);
+}
+
+/// Framed writes should stay hidden from readers until the tick is committed.
+#[tokio::test]
+async fn framed_commits() {
+    let frame = Slock::new_framed(0);
+    frame.set(|v| v + 1).await;
+    frame.set(|v| v + 1).await;
+    assert_eq!(frame.get_clone().await, 0);
+
+    assert!(frame.commit_tick().await);
+    assert_eq!(frame.get_clone().await, 2);
+    assert!(!frame.commit_tick().await);
+    assert_eq!(frame.visible().metrics().version, 1);
+
+    // A commit that finishes late must not replace a newer frame
+    frame.visible().pause_writes();
+    frame.set(|v| v + 1).await;
+    let mut older = Box::pin(frame.commit_tick());
+    assert!(futures::poll!(&mut older).is_pending());
+    frame.set(|v| v + 1).await;
+    let mut newer = Box::pin(frame.commit_tick());
+    assert!(futures::poll!(&mut newer).is_pending());
+    frame.visible().resume_writes();
+    assert!(newer.await);
+    assert!(!older.await);
+    assert_eq!(frame.get_clone().await, 4);
+}
+
+/// Recovery combinators should hand over the error instead of returning it.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn recovery_combinators() {
+    let lock = Slock::builder()
+        .timeout(Duration::from_millis(20))
+        .build(5i32);
+    assert_eq!(lock.read_or_recover(|_| -1).await, 5);
+
+    let busy = lock.clone();
+    let writer = in_thread(async move {
+        busy.set(|v| {
+            std::thread::sleep(Duration::from_millis(200));
+            v + 1
+        })
+        .await
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let recovered = lock
+        .read_or_recover(|e| match e {
+            Error::Stalled { .. } => -1,
+            _ => -2,
+        })
+        .await;
+    assert_eq!(recovered, -1);
+    writer.join().unwrap();
+
+    let validated = Slock::new_validated(0, |v| if *v >= 0 { Ok(()) } else { Err("negative") });
+    let mut refused = false;
+    validated.set_or_else(|v| v - 1, |_| refused = true).await;
+    assert!(refused);
+}
+
+/// Small locks should round trip their values, not lose concurrent sets, and survive panicking setters.
+#[tokio::test]
+async fn small_slocks() {
+    let hits = SmallSlock::new(0i32);
+    let tasks: Vec<_> = (0..8)
+        .map(|_| {
+            let hits = hits.clone();
+            tokio::spawn(async move {
+                for _ in 0..100 {
+                    hits.set(|v| v - 1).await;
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+    assert_eq!(hits.get().await, -800);
+
+    let ratio = SmallSlock::new(0.5f32);
+    assert_eq!(ratio.replace(-1.25).await, 0.5);
+    assert_eq!(ratio.map(|v| v * 2.0).await.unwrap(), -2.5);
+    let panicked: Result<f32, Error> = ratio.map(|_| panic!("mapper")).await;
+    assert!(matches!(panicked, Err(Error::ClosurePanicked)));
+    let panicked = ratio.try_set(|_| panic!("setter")).await;
+    assert!(matches!(panicked, Err(Error::ClosurePanicked)));
+    ratio.set(|_| panic!("setter")).await;
+    assert_eq!(ratio.get().await, -1.25);
+
+    let flag = SmallSlock::new(false);
+    let next = String::from("moved");
+    flag.set(move |v| {
+        drop(next);
+        !v
+    })
+    .await;
+    assert!(flag.get().await);
+}
+
+/// A registry should keep one lock per key and value type, and only find locks of the asked for type.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn any_slock_map() {
+    let registry = AnySlockMap::new();
+    registry.insert(1u32, 5i32).await;
+    registry.insert(1u32, String::from("five")).await;
+    let number = registry.get::<i32>(1).await.unwrap();
+    registry.insert(1u32, 6i32).await;
+
+    assert_eq!(number.get().await, 6);
+    assert_eq!(registry.len().await, 2);
+    assert!(registry.get::<u64>(1).await.is_none());
+    assert_eq!(registry.keys_of::<String>().await, vec![1]);
+
+    let text = registry.remove::<String>(1).await.unwrap();
+    assert_eq!(text.get_clone().await, "five");
+    assert!(registry.get::<i32>(1).await.is_some());
+
+    // Waiting on a busy lock shouldn't keep the registry locked
+    let lease = number.lease(Duration::from_secs(1)).await;
+    let ((), len) = tokio::join!(registry.insert(1u32, 7i32), async {
+        let len = tokio::time::timeout(Duration::from_millis(100), registry.len()).await;
+        drop(lease);
+        len
+    });
+    assert_eq!(len.unwrap(), 1);
+    assert_eq!(number.get().await, 7);
+}
+
+/// Zipped reads should see both sides of a paired update, or neither.
+#[tokio::test]
+async fn zipped_reads() {
+    let from = Slock::new(100i64);
+    let to = Slock::new(0i64);
+    let transfers = {
+        let (from, to) = (from.clone(), to.clone());
+        tokio::spawn(async move {
+            for _ in 0..50 {
+                slock::two_phase(&from, &to)
+                    .prepare(|a, b| Ok::<_, &str>((a - 1, b + 1)))
+                    .commit()
+                    .await
+                    .unwrap();
+                tokio::task::yield_now().await;
+            }
+        })
+    };
+    for _ in 0..50 {
+        let (a, b) = from.zip(&to).get().await;
+        assert_eq!(a + b, 100);
+        tokio::task::yield_now().await;
+    }
+    transfers.await.unwrap();
+    assert_eq!(to.zip(&from).get_clone().await, (50, 50));
+}
+
+/// Progress should add up work from several tasks, and stream each update.
+#[tokio::test]
+async fn progress() {
+    use futures::StreamExt;
+
+    let progress = Progress::new_progress(40);
+    let updates = progress.updates().await;
+    progress.set_stage("indexing").await;
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let progress = progress.clone();
+            tokio::spawn(async move { progress.advance(10).await })
+        })
+        .collect();
+    for worker in workers {
+        worker.await.unwrap();
+    }
+
+    assert_eq!(progress.fraction().await, 1.0);
+    let states: Vec<ProgressState> = updates.take(5).collect().await;
+    assert_eq!(states[0].stage.as_deref(), Some("indexing"));
+    assert!(states[4].is_finished());
+}
+
+/// Derived locks should update in dependency order, so hooks never see a half updated diamond.
+#[tokio::test]
+async fn graph_without_glitches() {
+    let graph = SlockGraph::new();
+    let a = graph.source(1).await;
+    let doubled = graph.derive(&a, |a| a * 2).await;
+    let tripled = graph.derive(&a, |a| a * 3).await;
+    let sum = graph.derive2(&doubled, &tripled, |d, t| d + t).await;
+
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = seen.clone();
+    sum.hook(move |v| log.lock().unwrap().push(*v)).await;
+
+    graph.set(&a, |_| 2).await;
+    graph.set(&a, |_| 3).await;
+    assert_eq!(*seen.lock().unwrap(), vec![10, 15]);
+
+    let _consistent = graph.consistent().await;
+    assert_eq!(sum.get().await, doubled.get().await + tripled.get().await);
+}
+
+/// Exports should restore into another map, keeping the locks of keys it already has.
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn map_export_import() {
+    let saved = SlockMap::new_map();
+    saved.insert("volume", |_| 7).await;
+    saved.insert("brightness", |_| 3).await;
+    let export = saved.export().await.unwrap();
+
+    let restored = SlockMap::new_map();
+    restored.insert("volume", |_| 0).await;
+    restored.insert("contrast", |_| 5).await;
+    let volume = restored.from_key("volume").await.unwrap();
+    restored.import(export.clone()).await;
+
+    assert_eq!(volume.get().await, 7);
+    assert!(restored.from_key("contrast").await.is_none());
+    assert_eq!(restored.snapshot().await.unwrap(), export);
+
+    // Waiting on a busy entry shouldn't keep the map locked
+    let lease = volume.lease(Duration::from_secs(1)).await;
+    let ((), found) = tokio::join!(restored.import(export), async {
+        let found =
+            tokio::time::timeout(Duration::from_millis(100), restored.from_key("volume")).await;
+        drop(lease);
+        found
+    });
+    assert!(found.unwrap().is_some());
+
+    saved
+        .from_key("volume")
+        .await
+        .unwrap()
+        .set(|_| panic!("setter"))
+        .await;
+    assert!(matches!(saved.export().await, Err(Error::Poisoned)));
+}
+
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn instrumented_calls_are_counted_and_named() {
+    let lock = Slock::builder().named("cart").build(0u32);
+    lock.instrument("add").set(|v| v + 1).await;
+    lock.instrument("add").set(|v| v + 1).await;
+    assert_eq!(lock.instrument("total").get().await, 2);
+
+    let callsites = lock.callsite_metrics();
+    assert_eq!(callsites["add"].acquisitions, 2);
+    assert_eq!(callsites["total"].acquisitions, 1);
+    assert_eq!(lock.metrics().acquisitions, 3);
+
+    let writer = in_thread({
+        let lock = lock.clone();
+        async move {
+            lock.instrument("checkout")
+                .set(|v| {
+                    std::thread::sleep(Duration::from_millis(100));
+                    v
+                })
+                .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    match lock.get_clone_timeout(Duration::from_millis(20)).await {
+        Err(Error::Stalled { writer, .. }) => assert_eq!(writer, Some("checkout")),
+        other => panic!("expected a stall, got {other:?}"),
+    }
+    writer.join().unwrap();
+    assert!(lock
+        .get_clone_timeout(Duration::from_millis(20))
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn invalidation_tokens_go_stale() {
+    let lock = Slock::new(1u32);
+    let other = Slock::new(1u32);
+    let token = lock.invalidation_token().await;
+    assert!(!token.is_stale(&lock).await);
+    assert!(token.is_stale(&other).await);
+
+    lock.map(|v| *v).await.unwrap();
+    assert!(!token.is_stale(&lock).await);
+
+    lock.set(|v| v + 1).await;
+    assert!(token.is_stale(&lock).await);
+    assert_eq!(token, token);
+    assert_ne!(token, lock.invalidation_token().await);
+
+    // A new lock is never mistaken for a dropped one, even at the same address
+    let dropped = Slock::new(7u32);
+    let token = dropped.invalidation_token().await;
+    drop(dropped);
+    let reused = Slock::new(7u32);
+    assert!(token.is_stale(&reused).await);
+}
+
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn rate_limiter_refills_over_time() {
+    let limiter = slock::util::RateLimiter::new(3, Duration::from_millis(50));
+    assert!(limiter.try_acquire(2).await);
+    assert!(!limiter.try_acquire(2).await);
+    assert_eq!(limiter.available().await, 1);
+
+    let started = std::time::Instant::now();
+    limiter.clone().acquire(3).await;
+    assert!(started.elapsed() >= Duration::from_millis(90));
+    assert_eq!(limiter.available().await, 0);
+}
+
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn circuit_breaker_opens_and_recovers() {
+    use slock::util::{BreakerState, CircuitBreaker};
+
+    let breaker = CircuitBreaker::new(2, Duration::from_millis(50));
+    let transitions = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = transitions.clone();
+    breaker
+        .on_transition(move |from, to| log.lock().unwrap().push((from, to)))
+        .await;
+
+    let fail = || async { Err::<(), _>("down") };
+    assert!(matches!(breaker.call(fail).await, Ok(Err("down"))));
+    assert_eq!(breaker.state().await, BreakerState::Closed);
+    assert!(matches!(breaker.call(fail).await, Ok(Err("down"))));
+    assert_eq!(breaker.state().await, BreakerState::Open);
+
+    let mut called = false;
+    let refused = breaker
+        .call(|| {
+            called = true;
+            async { Ok::<_, ()>(()) }
+        })
+        .await;
+    assert!(matches!(refused, Err(Error::CircuitOpen)));
+    assert!(!called);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(matches!(breaker.call(fail).await, Ok(Err("down"))));
+    assert_eq!(breaker.state().await, BreakerState::Open);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(matches!(
+        breaker.call(|| async { Ok::<_, ()>(7) }).await,
+        Ok(Ok(7))
+    ));
+    assert_eq!(breaker.state().await, BreakerState::Closed);
+    assert_eq!(breaker.failures().await, 0);
+
+    use BreakerState::*;
+    assert_eq!(
+        *transitions.lock().unwrap(),
+        vec![
+            (Closed, Open),
+            (Open, HalfOpen),
+            (HalfOpen, Open),
+            (Open, HalfOpen),
+            (HalfOpen, Closed)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn zero_sized_get_skips_the_lock() {
+    let lock = Slock::new(());
+    let writer = in_thread({
+        let lock = lock.clone();
+        async move {
+            lock.set(|v| {
+                std::thread::sleep(Duration::from_millis(100));
+                v
+            })
+            .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(30)).await;
+
+    let read = tokio::time::timeout(Duration::from_millis(20), lock.get()).await;
+    assert!(read.is_ok());
+    writer.join().unwrap();
+    assert_eq!(lock.metrics().acquisitions, 1);
+    assert_eq!(lock.metrics().version, 1);
+}
+
+#[tokio::test]
+async fn signal_wakes_waiters_by_count() {
+    let signal = Signal::new();
+    let hooked = std::sync::Arc::new(AtomicU8::new(0));
+    let hits = hooked.clone();
+    signal
+        .lock()
+        .hook(move |_| {
+            hits.fetch_add(1, Ordering::Relaxed);
+        })
+        .await;
+
+    let waiter = tokio::spawn({
+        let signal = signal.clone();
+        async move { signal.wait_count(2).await }
+    });
+    let next = tokio::spawn({
+        let signal = signal.clone();
+        async move { signal.wait().await }
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    signal.notify().await;
+    assert_eq!(next.await.unwrap(), 1);
+    assert!(!waiter.is_finished());
+    signal.notify().await;
+    assert_eq!(waiter.await.unwrap(), 2);
+    assert_eq!(signal.wait_count(1).await, 2);
+    assert_eq!(hooked.load(Ordering::Relaxed), 2);
+}
+
+/// A setter panicking on a lock with no validator poisons it, rather than taking the process down.
+#[tokio::test]
+async fn closure_panics_without_validator() {
+    let lock = Slock::new(vec![1, 2, 3]);
+    let set = lock.try_set(|_| panic!("setter")).await;
+    assert!(matches!(set, Err(Error::ClosurePanicked)));
+    assert!(lock.is_poisoned());
+
+    assert!(matches!(lock.try_set(|v| v).await, Err(Error::Poisoned)));
+    assert!(matches!(lock.map(|v| v.len()).await, Err(Error::Poisoned)));
+
+    let plain = Slock::new(1i32);
+    plain.set(|_| panic!("setter")).await;
+    assert!(plain.is_poisoned());
+    let reader = plain.clone();
+    let read = tokio::spawn(async move { reader.get().await }).await;
+    assert!(read.unwrap_err().is_panic());
+}